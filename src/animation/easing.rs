@@ -8,6 +8,28 @@ pub enum Easing {
 pub fn evaluate_easing_progress(easing: Easing, progress: f32) -> f32 {
     match easing {
         Easing::Linear => progress,
-        _ => progress,
+        // Smoothstep, starts and ends at rest
+        Easing::EaseInOut => progress * progress * (3.0 - 2.0 * progress),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ease_in_out_starts_and_ends_slowly() {
+        let ease = |progress| evaluate_easing_progress(Easing::EaseInOut, progress);
+
+        assert_eq!(ease(0.0), 0.0);
+        assert_eq!(ease(0.5), 0.5);
+        assert_eq!(ease(1.0), 1.0);
+        assert!(ease(0.1) < 0.1 && ease(0.9) > 0.9);
+        assert!((ease(0.25) + ease(0.75) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn linear_keeps_the_progress() {
+        assert_eq!(evaluate_easing_progress(Easing::Linear, 0.3), 0.3);
     }
 }
@@ -0,0 +1,42 @@
+/// Values that can be animated by **keyframes**
+pub trait Interpolate: Clone {
+    /// Interpolates between `self` and `other`, a **progress** of 0 yields `self` and 1 yields `other`
+    fn interpolate(&self, other: &Self, progress: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        self + (other - self) * progress
+    }
+}
+
+impl Interpolate for bool {
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        // Switch once the animation is done
+        if progress < 1.0 {
+            *self
+        } else {
+            *other
+        }
+    }
+}
+
+impl Interpolate for u32 {
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        // Counts step to the nearest whole number
+        (*self as f32)
+            .interpolate(&(*other as f32), progress)
+            .round() as u32
+    }
+}
+
+impl<T: Interpolate> Interpolate for Option<T> {
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        match (self, other) {
+            (Some(from), Some(to)) => Some(from.interpolate(to, progress)),
+            // Appearing or disappearing values switch once the animation is done
+            _ if progress < 1.0 => self.clone(),
+            _ => other.clone(),
+        }
+    }
+}
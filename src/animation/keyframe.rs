@@ -1,40 +1,57 @@
-use crate::animation::Easing;
+use crate::animation::{evaluate_easing_progress, Easing, Interpolate};
 use crate::element::private::Element;
+use std::sync::Arc;
+
+/// Raw **keyframe** data as passed by the `animate!` macro: setter, start value, end value, start time, duration and easing
+pub type KeyframeData<T, V> = (fn(&mut T, V), V, V, f32, f32, Easing);
+
+/// Sets the value interpolated at the given eased progress on the element
+type ApplyFn<T> = Arc<dyn Fn(&mut T, f32) + Send + Sync>;
 
 #[derive(Clone)]
 pub struct Keyframe<T: Element + ?Sized> {
-    pub setter_fn: fn(&mut T, f32),
-    pub start_value: f32,
-    pub end_value: f32,
+    apply_fn: ApplyFn<T>,
     pub start_time: f32,
     pub duration: f32,
     pub easing: Easing,
 }
 
-impl<T: Element> Keyframe<T> {
-    /// Updates the underlying value of the given **element**
-    pub fn update_keyframe_data(&self, element: &mut T, time_since_start: f32) {
-        let progress =
-            1.0 - (((self.start_time + self.duration) - time_since_start) / self.duration);
-        let eased_progress = self.get_progress_with_easing(progress);
+impl<T: Element + 'static> Keyframe<T> {
+    /// Creates a new **keyframe** that animates a value from **start_value** to **end_value**
+    pub fn new<V: Interpolate + Send + Sync + 'static>(keyframe_data: KeyframeData<T, V>) -> Self {
+        let (setter_fn, start_value, end_value, start_time, duration, easing) = keyframe_data;
+
+        Keyframe {
+            apply_fn: Arc::new(move |element, eased_progress| {
+                setter_fn(element, start_value.interpolate(&end_value, eased_progress))
+            }),
+            start_time,
+            duration,
+            easing,
+        }
+    }
 
-        let diff = self.end_value - self.start_value;
+    /// Updates the underlying value of the given **element**, keyframes hold their start value before and their end value after they are active
+    pub fn update_keyframe_data(&self, element: &mut T, time_since_start: f32) {
+        let progress = if self.duration > 0.0 {
+            ((time_since_start - self.start_time) / self.duration).clamp(0.0, 1.0)
+        } else if self.has_started(time_since_start) {
+            1.0
+        } else {
+            0.0
+        };
 
         // Update value based on eased progress value
-        (self.setter_fn)(element, self.start_value + eased_progress * diff);
+        (self.apply_fn)(element, self.get_progress_with_easing(progress));
     }
 
-    /// Determines whether the **keyframe** is currently active
-    pub fn is_active(&self, time_since_start: f32) -> bool {
-        (time_since_start >= self.start_time)
-            && (time_since_start <= self.start_time + self.duration)
+    /// Determines whether the **keyframe** has started
+    pub fn has_started(&self, time_since_start: f32) -> bool {
+        time_since_start >= self.start_time
     }
 
     /// Get the current progress as eased number between 0 and 1
     fn get_progress_with_easing(&self, progress: f32) -> f32 {
-        match self.easing {
-            Easing::Linear => progress,
-            _ => progress,
-        }
+        evaluate_easing_progress(self.easing.clone(), progress)
     }
 }
@@ -0,0 +1,42 @@
+/// Describes when an **element** is part of the scene, as a list of `[added, removed)` intervals
+#[derive(Debug, Clone, Default)]
+pub struct Lifespan {
+    /// Time intervals in which the **element** is alive, an open end means it is never removed
+    intervals: Vec<(f32, Option<f32>)>,
+}
+
+impl Lifespan {
+    /// Creates a new **Lifespan** starting at the given time
+    pub fn starting_at(time: f32) -> Self {
+        Self {
+            intervals: vec![(time, None)],
+        }
+    }
+
+    /// Adds the **element** to the scene at the given time, does nothing if it is already alive by then
+    pub fn add(&mut self, time: f32) {
+        if !self.is_open() {
+            self.intervals.push((time, None));
+        }
+    }
+
+    /// Removes the **element** from the scene at the given time, does nothing if it is not alive
+    pub fn remove(&mut self, time: f32) {
+        if let Some((start, end @ None)) = self.intervals.last_mut() {
+            // Removing before the element was added is the same as never adding it
+            *end = Some(time.max(*start));
+        }
+    }
+
+    /// Determines whether the **element** is part of the scene at the given time
+    pub fn is_alive(&self, time: f32) -> bool {
+        self.intervals
+            .iter()
+            .any(|(start, end)| time >= *start && end.is_none_or(|end| time < end))
+    }
+
+    /// Determines whether the last interval has not been closed by a removal yet
+    fn is_open(&self) -> bool {
+        matches!(self.intervals.last(), Some((_, None)))
+    }
+}
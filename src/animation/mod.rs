@@ -1,9 +1,14 @@
 mod easing;
+mod interpolate;
 mod keyframe;
+mod lifespan;
 mod scene;
 
 pub use easing::evaluate_easing_progress;
 pub use easing::Easing;
+pub use interpolate::Interpolate;
 pub use keyframe::Keyframe;
+pub use keyframe::KeyframeData;
+pub use lifespan::Lifespan;
 pub use scene::Scene;
 pub use scene::DOM;
@@ -1,8 +1,6 @@
+use crate::animation::Lifespan;
 use crate::element::Element;
-use crate::{Arrow, Keyframe};
-use std::borrow::BorrowMut;
 use std::collections::HashMap;
-use std::f32::consts::PI;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -34,6 +32,7 @@ impl Scene {
         &mut self,
         scene_dom: Arc<Mutex<DOM>>,
         mut elements: HashMap<usize, Box<dyn Element + Send>>,
+        lifespans: HashMap<usize, Lifespan>,
     ) {
         // Create connection to render thread
         self.dom = Some(scene_dom);
@@ -41,12 +40,27 @@ impl Scene {
         // TODO: Check whether this is necessary
         //self.start_time = Instant::now();
 
+        // Group membership does not change during the animation, so the parents are only determined once
+        let mut parents: HashMap<usize, Vec<usize>> = HashMap::new();
+        elements.values().for_each(|element| {
+            element
+                .get_children()
+                .iter()
+                .for_each(|child_id| parents.entry(*child_id).or_default().push(element.get_id()))
+        });
+
         // start rendering the scene
         loop {
             let time_since_start = self.time_since_start().as_secs_f32();
             println!("Time since start: {:?}", time_since_start);
 
             elements.iter_mut().for_each(|(element_id, element)| {
+                // Only elements that are currently part of the scene are passed to the renderer
+                if !is_alive(*element_id, &lifespans, &parents, time_since_start) {
+                    self.dom_copy.remove(element_id);
+                    return;
+                }
+
                 // Update all keyframes that have to be updated
                 element.update_data_with_keyframes(time_since_start);
 
@@ -80,3 +94,122 @@ impl Scene {
         }
     }
 }
+
+/// Determines whether an **element** is part of the scene at the given time, under at least one of its parent groups
+///
+/// An element in several groups is alive as long as one of them is, the renderer only draws it within the groups that are
+/// alive. Groups that contain each other form a cycle of parents, which is only walked once.
+fn is_alive(
+    element_id: usize,
+    lifespans: &HashMap<usize, Lifespan>,
+    parents: &HashMap<usize, Vec<usize>>,
+    time: f32,
+) -> bool {
+    is_alive_within(element_id, lifespans, parents, time, &mut vec![])
+}
+
+/// Walks up the parents like `is_alive`, the **descendants** are the elements the walk came from
+fn is_alive_within(
+    element_id: usize,
+    lifespans: &HashMap<usize, Lifespan>,
+    parents: &HashMap<usize, Vec<usize>>,
+    time: f32,
+    descendants: &mut Vec<usize>,
+) -> bool {
+    // The walk came around a cycle, the groups on it were alive so far
+    if descendants.contains(&element_id) {
+        return true;
+    }
+
+    let is_element_alive = lifespans
+        .get(&element_id)
+        .is_some_and(|lifespan| lifespan.is_alive(time));
+    if !is_element_alive {
+        return false;
+    }
+
+    let Some(element_parents) = parents.get(&element_id).filter(|ids| !ids.is_empty()) else {
+        return true;
+    };
+
+    descendants.push(element_id);
+    let is_parent_alive = element_parents
+        .iter()
+        .any(|parent_id| is_alive_within(*parent_id, lifespans, parents, time, descendants));
+    descendants.pop();
+
+    is_parent_alive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_containing_each_other_are_alive() {
+        let lifespans = HashMap::from([
+            (1, Lifespan::starting_at(0.0)),
+            (2, Lifespan::starting_at(1.0)),
+        ]);
+        let parents = HashMap::from([(1, vec![2]), (2, vec![1])]);
+
+        assert!(is_alive(1, &lifespans, &parents, 2.0));
+        assert!(!is_alive(1, &lifespans, &parents, 0.5));
+    }
+
+    #[test]
+    fn elements_in_several_groups_live_as_long_as_one_of_them() {
+        let mut removed = Lifespan::starting_at(0.0);
+        removed.remove(1.0);
+        let lifespans = HashMap::from([
+            (1, Lifespan::starting_at(0.0)),
+            (2, removed),
+            (3, Lifespan::starting_at(2.0)),
+        ]);
+        let parents = HashMap::from([(1, vec![2, 3])]);
+
+        assert!(is_alive(1, &lifespans, &parents, 0.5));
+        assert!(!is_alive(1, &lifespans, &parents, 1.5));
+        assert!(is_alive(1, &lifespans, &parents, 2.5));
+    }
+
+    #[test]
+    fn lifespans_cover_the_time_between_add_and_remove() {
+        let mut lifespan = Lifespan::starting_at(1.0);
+        lifespan.remove(3.0);
+
+        assert!(!lifespan.is_alive(0.5));
+        assert!(lifespan.is_alive(1.0));
+        assert!(lifespan.is_alive(2.9));
+        assert!(!lifespan.is_alive(3.0));
+    }
+
+    #[test]
+    fn removing_before_adding_never_shows_the_element() {
+        let mut lifespan = Lifespan::starting_at(2.0);
+        lifespan.remove(1.0);
+
+        assert!(!lifespan.is_alive(1.5));
+        assert!(!lifespan.is_alive(2.0));
+        assert!(!lifespan.is_alive(5.0));
+    }
+
+    #[test]
+    fn elements_can_be_added_again_after_removal() {
+        let mut lifespan = Lifespan::starting_at(0.0);
+        lifespan.remove(1.0);
+        lifespan.add(2.0);
+
+        // Adding an element that is alive already keeps the earlier start
+        lifespan.add(3.0);
+
+        assert!(lifespan.is_alive(0.5));
+        assert!(!lifespan.is_alive(1.5));
+        assert!(lifespan.is_alive(2.0));
+        assert!(lifespan.is_alive(10.0));
+
+        lifespan.remove(4.0);
+        assert!(lifespan.is_alive(3.5));
+        assert!(!lifespan.is_alive(4.0));
+    }
+}
@@ -0,0 +1,483 @@
+use crate::element::private::Element as _;
+use crate::element::{polylines_to_path, sample_curve};
+use crate::{Arrow, Color, DecimalNumber, Group, Line, Path, TextAlign};
+use lyon::math::{point, Point};
+use std::ops::RangeInclusive;
+
+/// Size of the axes relative to the largest deviation of plotted graphs from the exact ones
+const PLOT_RESOLUTION: f32 = 4096.0;
+
+/// How **Axes** are drawn
+#[derive(Debug, Clone)]
+pub struct AxesStyle {
+    /// Width of the axes in scene units, the x range is stretched to it
+    pub width: f32,
+    /// Height of the axes in scene units, the y range is stretched to it
+    pub height: f32,
+    /// Distance between ticks on the x axis in data units, `None` picks a round step that gives a handful of ticks
+    pub x_step: Option<f32>,
+    /// Distance between ticks on the y axis in data units, `None` picks a round step that gives a handful of ticks
+    pub y_step: Option<f32>,
+    /// Length of the ticks across their axis
+    pub tick_length: f32,
+    /// Whether ticks are labeled with their value, the value where the other axis crosses is left out
+    pub tick_labels: bool,
+    /// Height of the em square of the tick labels
+    pub font_size: f32,
+    /// Whether grid lines are drawn at every tick
+    pub grid: bool,
+    /// Color of the axes, ticks and tick labels
+    pub color: Color,
+    pub grid_color: Color,
+    /// Width of the axes and ticks, grid lines are half as wide
+    pub stroke_width: f32,
+    /// Whether the axes end in arrow tips beyond their range
+    pub tips: bool,
+}
+
+impl Default for AxesStyle {
+    fn default() -> Self {
+        AxesStyle {
+            width: 6.0,
+            height: 4.0,
+            x_step: None,
+            y_step: None,
+            tick_length: 0.15,
+            tick_labels: true,
+            font_size: 0.2,
+            grid: false,
+            color: Color::BLACK,
+            grid_color: Color::GRAY,
+            stroke_width: 0.03,
+            tips: true,
+        }
+    }
+}
+
+/// Coordinate **Axes** for plotting functions, data coordinates are mapped onto a rectangle centered on the origin of the group
+///
+/// The axes cross at 0, or at the end of a range that doesn't contain 0. Use `c2p` to place other elements at data coordinates.
+///
+/// **Axes** are not an element themselves, add their **group** to the scene to show them, together with everything plotted.
+pub struct Axes {
+    /// Group containing all parts of the axes and the plotted graphs, move it to place the whole axes
+    pub group: Group,
+    pub x_axis: Arrow,
+    pub y_axis: Arrow,
+    /// Ticks of the x axis followed by the ticks of the y axis
+    pub ticks: Vec<Line>,
+    /// Labels of the x ticks followed by the labels of the y ticks
+    pub tick_labels: Vec<DecimalNumber>,
+    /// Vertical grid lines followed by horizontal ones, empty unless enabled in the style
+    pub grid: Vec<Line>,
+    x_range: RangeInclusive<f32>,
+    y_range: RangeInclusive<f32>,
+    width: f32,
+    height: f32,
+}
+
+impl Axes {
+    /// Creates axes spanning the given data ranges, with the default style
+    pub fn new(x_range: RangeInclusive<f32>, y_range: RangeInclusive<f32>) -> Self {
+        Axes::with_style(x_range, y_range, &AxesStyle::default())
+    }
+
+    /// Creates axes spanning the given data ranges like `new`, drawn with the given **style**
+    pub fn with_style(
+        x_range: RangeInclusive<f32>,
+        y_range: RangeInclusive<f32>,
+        style: &AxesStyle,
+    ) -> Self {
+        let mut axes = Axes {
+            group: Group::default(),
+            x_axis: Arrow::default(),
+            y_axis: Arrow::default(),
+            ticks: vec![],
+            tick_labels: vec![],
+            grid: vec![],
+            x_range,
+            y_range,
+            width: style.width.max(0.0),
+            height: style.height.max(0.0),
+        };
+
+        let (x_min, x_max) = (*axes.x_range.start(), *axes.x_range.end());
+        let (y_min, y_max) = (*axes.y_range.start(), *axes.y_range.end());
+        let (left, bottom) = axes.local(x_min, y_min).to_tuple();
+        let (right, top) = axes.local(x_max, y_max).to_tuple();
+
+        // Each axis lies where the other one is 0, or at its nearest end
+        let x_crossing = 0.0_f32.clamp(x_min.min(x_max), x_min.max(x_max));
+        let y_crossing = 0.0_f32.clamp(y_min.min(y_max), y_min.max(y_max));
+        let origin = axes.local(x_crossing, y_crossing);
+
+        let x_step = step(&axes.x_range, style.x_step);
+        let y_step = step(&axes.y_range, style.y_step);
+        let x_ticks = ticks(&axes.x_range, x_step);
+        let y_ticks = ticks(&axes.y_range, y_step);
+        let line = |start: Point, end: Point, color: Color, width: f32| Line {
+            start_x: start.x,
+            start_y: start.y,
+            end_x: end.x,
+            end_y: end.y,
+            fill: false,
+            stroke: true,
+            stroke_color: color,
+            stroke_width: width,
+            ..Line::default()
+        };
+
+        if style.grid {
+            for x in &x_ticks {
+                let x = axes.local(*x, 0.0).x;
+                axes.grid.push(line(
+                    point(x, bottom),
+                    point(x, top),
+                    style.grid_color,
+                    style.stroke_width / 2.0,
+                ));
+            }
+            for y in &y_ticks {
+                let y = axes.local(0.0, *y).y;
+                axes.grid.push(line(
+                    point(left, y),
+                    point(right, y),
+                    style.grid_color,
+                    style.stroke_width / 2.0,
+                ));
+            }
+        }
+
+        // Tips extend the axes beyond their range, so the last tick stays visible. The tip keeps its width without tips,
+        // which leaves a plain shaft instead of one clamped to nothing.
+        let tip_size = 6.0 * style.stroke_width;
+        let tip_length = if style.tips { tip_size } else { 0.0 };
+        let axis = |start: Point, end: Point| Arrow {
+            start_x: start.x,
+            start_y: start.y,
+            end_x: end.x,
+            end_y: end.y,
+            shaft_width: style.stroke_width,
+            tip_length,
+            tip_width: tip_size,
+            fill_color: style.color,
+            ..Arrow::default()
+        };
+        axes.x_axis = axis(point(left, origin.y), point(right + tip_length, origin.y));
+        axes.y_axis = axis(point(origin.x, bottom), point(origin.x, top + tip_length));
+
+        let half_tick = style.tick_length / 2.0;
+        let label = |value: f32, step: f32| DecimalNumber {
+            value,
+            decimal_places: decimal_places(step),
+            font_size: style.font_size,
+            fill_color: style.color,
+            ..DecimalNumber::default()
+        };
+
+        for x in &x_ticks {
+            let position = axes.local(*x, y_crossing);
+            axes.ticks.push(line(
+                point(position.x, position.y - half_tick),
+                point(position.x, position.y + half_tick),
+                style.color,
+                style.stroke_width,
+            ));
+
+            if style.tick_labels && *x != x_crossing {
+                // Labels hang below the tick, digits are about three quarters of the font size high
+                axes.tick_labels.push(DecimalNumber {
+                    x: position.x,
+                    y: position.y - half_tick - 0.85 * style.font_size,
+                    align: TextAlign::Center,
+                    ..label(*x, x_step)
+                });
+            }
+        }
+
+        for y in &y_ticks {
+            let position = axes.local(x_crossing, *y);
+            axes.ticks.push(line(
+                point(position.x - half_tick, position.y),
+                point(position.x + half_tick, position.y),
+                style.color,
+                style.stroke_width,
+            ));
+
+            if style.tick_labels && *y != y_crossing {
+                // Labels sit left of the tick, centered on it
+                axes.tick_labels.push(DecimalNumber {
+                    x: position.x - half_tick - 0.2 * style.font_size,
+                    y: position.y - 0.36 * style.font_size,
+                    align: TextAlign::Right,
+                    ..label(*y, y_step)
+                });
+            }
+        }
+
+        for grid_line in &axes.grid {
+            axes.group.add(grid_line);
+        }
+        axes.group.add(&axes.x_axis);
+        axes.group.add(&axes.y_axis);
+        for tick in &axes.ticks {
+            axes.group.add(tick);
+        }
+        for tick_label in &axes.tick_labels {
+            axes.group.add(tick_label);
+        }
+
+        axes
+    }
+
+    /// Converts data coordinates to a point in the space the group is placed in, taking the transform of the group into account
+    ///
+    /// Elements placed in the same space as the axes, like an **Arrow** next to them, line up with the data at the returned point.
+    pub fn c2p(&self, x: f32, y: f32) -> (f32, f32) {
+        self.group
+            .get_transform()
+            .matrix()
+            .transform_point(self.local(x, y))
+            .to_tuple()
+    }
+
+    /// Plots the graph of a **function** over the x range with the given stroke, the graph is added to the group and clipped to the y range
+    ///
+    /// The function is sampled more densely where the graph bends, it is interrupted where the function is not finite or jumps,
+    /// like at the poles of `tan`. The graph can be drawn on by animating **trim_end** from 0 to 1.
+    ///
+    /// The group holds a copy of the graph as it is now, so later changes to the returned **Path** only show through `animate!`.
+    pub fn plot(&mut self, function: impl Fn(f32) -> f32, color: Color, stroke_width: f32) -> Path {
+        let (x_min, x_max) = (*self.x_range.start(), *self.x_range.end());
+
+        // The graph is sampled once, fine enough for the axes to fill the screen however the group is scaled
+        let tolerance = self.width.max(self.height) / PLOT_RESOLUTION;
+        let polylines = sample_curve(|x| self.local(x, function(x)), x_min, x_max, tolerance);
+
+        let clipped: Vec<Vec<Point>> = polylines
+            .iter()
+            .flat_map(|polyline| clip_polyline(polyline, -self.height / 2.0, self.height / 2.0))
+            .collect();
+
+        let graph = Path {
+            geometry: polylines_to_path(&clipped),
+            fill: false,
+            stroke: true,
+            stroke_color: color,
+            stroke_width,
+            ..Path::default()
+        };
+        self.group.add(&graph);
+
+        graph
+    }
+
+    /// Converts data coordinates to coordinates relative to the origin of the group
+    fn local(&self, x: f32, y: f32) -> Point {
+        let fraction = |value: f32, range: &RangeInclusive<f32>| {
+            let span = range.end() - range.start();
+
+            // Empty ranges map to the middle instead of dividing by zero
+            if span == 0.0 {
+                0.5
+            } else {
+                (value - range.start()) / span
+            }
+        };
+
+        point(
+            (fraction(x, &self.x_range) - 0.5) * self.width,
+            (fraction(y, &self.y_range) - 0.5) * self.height,
+        )
+    }
+}
+
+/// Returns a step of 1, 2 or 5 times a power of ten that splits the **range** into about 3 to 6 parts
+fn nice_step(range: &RangeInclusive<f32>) -> f32 {
+    let rough = (range.end() - range.start()).abs() / 6.0;
+    if !rough.is_normal() {
+        return 1.0;
+    }
+
+    let magnitude = 10.0_f32.powf(rough.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Returns the given **step** between ticks if it is valid, or a round one for the **range**
+fn step(range: &RangeInclusive<f32>, step: Option<f32>) -> f32 {
+    step.filter(|step| step.is_normal() && *step > 0.0)
+        .unwrap_or_else(|| nice_step(range))
+}
+
+/// Returns the multiples of the **step** within the **range**, in increasing order
+fn ticks(range: &RangeInclusive<f32>, step: f32) -> Vec<f32> {
+    let (min, max) = (
+        range.start().min(*range.end()),
+        range.start().max(*range.end()),
+    );
+
+    // A little slack keeps ticks at the ends of the range despite rounding errors
+    let first = (min / step - 1e-4).ceil() as i64;
+    let last = (max / step + 1e-4).floor() as i64;
+
+    // Steps far too small for the range would create an endless number of ticks
+    if last - first > 1000 {
+        return vec![];
+    }
+
+    (first..=last).map(|index| index as f32 * step).collect()
+}
+
+/// Returns the number of decimal places needed to show multiples of the **step**
+fn decimal_places(step: f32) -> u32 {
+    (0..6)
+        .find(|places| {
+            let scaled = step * 10.0_f32.powi(*places as i32);
+            (scaled - scaled.round()).abs() < 1e-3 * scaled.abs().max(1.0)
+        })
+        .unwrap_or(6)
+}
+
+/// Splits a **polyline** into the parts between **bottom** and **top**, segments crossing the bounds are cut where they cross
+fn clip_polyline(polyline: &[Point], bottom: f32, top: f32) -> Vec<Vec<Point>> {
+    let mut parts = vec![];
+    let mut current: Vec<Point> = vec![];
+
+    for segment in polyline.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+
+        // Range of the segment's parameter where it is within the bounds
+        let (enter, exit) = if from.y == to.y {
+            if (bottom..=top).contains(&from.y) {
+                (0.0, 1.0)
+            } else {
+                (1.0, 0.0)
+            }
+        } else {
+            let (at_bottom, at_top) = (
+                (bottom - from.y) / (to.y - from.y),
+                (top - from.y) / (to.y - from.y),
+            );
+            (
+                at_bottom.min(at_top).max(0.0),
+                at_bottom.max(at_top).min(1.0),
+            )
+        };
+        if enter > exit {
+            continue;
+        }
+
+        let (start, end) = (from.lerp(to, enter), from.lerp(to, exit));
+        if current.last() != Some(&start) {
+            if current.len() > 1 {
+                parts.push(std::mem::take(&mut current));
+            }
+            current = vec![start];
+        }
+        current.push(end);
+
+        // The segment leaves the bounds, the next part starts where it comes back
+        if exit < 1.0 {
+            parts.push(std::mem::take(&mut current));
+        }
+    }
+
+    if current.len() > 1 {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::private::Shape as _;
+    use lyon::algorithms::aabb::bounding_rect;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn tipless_axes_keep_their_shaft() {
+        let style = AxesStyle {
+            tips: false,
+            ..AxesStyle::default()
+        };
+        let axes = Axes::with_style(-1.0..=1.0, -1.0..=1.0, &style);
+
+        // Without tips the axes span exactly the width and height of the axes
+        for (axis, length) in [(&axes.x_axis, style.width), (&axes.y_axis, style.height)] {
+            let path = axis.build_path(0.01);
+            assert!(path.iter().next().is_some());
+
+            let size = bounding_rect(path.iter()).size;
+            assert_close(size.width.min(size.height), style.stroke_width);
+            assert_close(size.width.max(size.height), length);
+        }
+    }
+
+    #[test]
+    fn nice_steps_give_a_handful_of_ticks() {
+        assert_close(nice_step(&(0.0..=1.0)), 0.2);
+        assert_close(nice_step(&(-3.0..=7.0)), 2.0);
+        assert_close(nice_step(&(0.0..=1000.0)), 200.0);
+
+        // Empty ranges still get a usable step
+        assert_eq!(nice_step(&(2.0..=2.0)), 1.0);
+    }
+
+    #[test]
+    fn decimal_places_show_the_step() {
+        assert_eq!(decimal_places(0.25), 2);
+        assert_eq!(decimal_places(10.0), 0);
+        assert_eq!(decimal_places(0.1), 1);
+    }
+
+    #[test]
+    fn c2p_maps_range_corners_to_the_rectangle_corners() {
+        let axes = Axes::new(0.0..=10.0, -1.0..=1.0);
+
+        assert_eq!(axes.c2p(0.0, -1.0), (-3.0, -2.0));
+        assert_eq!(axes.c2p(10.0, 1.0), (3.0, 2.0));
+        assert_eq!(axes.c2p(0.0, 1.0), (-3.0, 2.0));
+        assert_eq!(axes.c2p(10.0, -1.0), (3.0, -2.0));
+    }
+
+    #[test]
+    fn polylines_leaving_the_range_are_split_where_they_cross() {
+        let polyline = [
+            point(0.0, 0.0),
+            point(1.0, 2.0),
+            point(2.0, 0.0),
+            point(3.0, -2.0),
+            point(4.0, 0.0),
+        ];
+
+        assert_eq!(
+            clip_polyline(&polyline, -1.0, 1.0),
+            vec![
+                vec![point(0.0, 0.0), point(0.5, 1.0)],
+                vec![point(1.5, 1.0), point(2.0, 0.0), point(2.5, -1.0)],
+                vec![point(3.5, -1.0), point(4.0, 0.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn polylines_within_the_range_stay_whole() {
+        let polyline = [point(0.0, 0.0), point(1.0, 0.5), point(2.0, 1.0)];
+
+        assert_eq!(clip_polyline(&polyline, -1.0, 1.0), vec![polyline.to_vec()]);
+        assert!(clip_polyline(&polyline, 2.0, 3.0).is_empty());
+    }
+}
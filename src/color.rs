@@ -0,0 +1,422 @@
+use crate::animation::Interpolate;
+
+/// Color spaces a **color** can be interpolated in while animating
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Component wise in linear light, physically correct blending of light
+    LinearRgb,
+    /// Along the hue circle, taking the shorter way around
+    Hsl,
+    /// Perceptually uniform, keeps midpoints bright and saturated
+    OkLab,
+}
+
+/// A **color** in sRGB, all components are between 0 and 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+    /// Space used when animating towards this color
+    pub interpolation_space: ColorSpace,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const GRAY: Color = Color::rgb(0.62, 0.62, 0.62);
+    pub const RED: Color = Color::rgb(0.9, 0.22, 0.21);
+    pub const ORANGE: Color = Color::rgb(0.98, 0.55, 0.0);
+    pub const YELLOW: Color = Color::rgb(0.99, 0.85, 0.21);
+    pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+    pub const TEAL: Color = Color::rgb(0.0, 0.54, 0.48);
+    pub const BLUE: Color = Color::rgb(0.12, 0.53, 0.9);
+    pub const PURPLE: Color = Color::rgb(0.56, 0.14, 0.67);
+    pub const PINK: Color = Color::rgb(0.85, 0.11, 0.38);
+    pub const TRANSPARENT: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
+
+    /// Creates an opaque **color** from its red, green and blue components
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Color::rgba(r, g, b, 1.0)
+    }
+
+    /// Creates a **color** from its red, green, blue and alpha components
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Color {
+            r,
+            g,
+            b,
+            a,
+            interpolation_space: ColorSpace::LinearRgb,
+        }
+    }
+
+    /// Creates a **color** from a hex string like `#1e88e5`, the `#` is optional and `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa` are supported
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is not a valid hex color, use `try_hex` to handle invalid input
+    pub fn hex(hex: &str) -> Self {
+        Color::try_hex(hex).unwrap_or_else(|| panic!("Invalid hex color: {:?}", hex))
+    }
+
+    /// Creates a **color** from a hex string like `#1e88e5`, returns `None` if the string is not a valid hex color
+    pub fn try_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        if !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        // Parses a single component from its hex digits, short forms repeat the digit
+        let component = |range: std::ops::Range<usize>| -> Option<f32> {
+            let value = u8::from_str_radix(&digits[range.clone()], 16).ok()?;
+
+            Some(match range.len() {
+                1 => (value * 17) as f32 / 255.0,
+                _ => value as f32 / 255.0,
+            })
+        };
+
+        match digits.len() {
+            3 => Some(Color::rgb(
+                component(0..1)?,
+                component(1..2)?,
+                component(2..3)?,
+            )),
+            4 => Some(Color::rgba(
+                component(0..1)?,
+                component(1..2)?,
+                component(2..3)?,
+                component(3..4)?,
+            )),
+            6 => Some(Color::rgb(
+                component(0..2)?,
+                component(2..4)?,
+                component(4..6)?,
+            )),
+            8 => Some(Color::rgba(
+                component(0..2)?,
+                component(2..4)?,
+                component(4..6)?,
+                component(6..8)?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Creates an opaque **color** from its hue in degrees, saturation and lightness between 0 and 1
+    pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        Color::hsla(hue, saturation, lightness, 1.0)
+    }
+
+    /// Creates a **color** from its hue in degrees, saturation, lightness and alpha between 0 and 1
+    pub fn hsla(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = lightness - chroma / 2.0;
+
+        Color::rgba(r + m, g + m, b + m, alpha)
+    }
+
+    /// Creates an opaque **color** from its OKLab lightness and its a and b axes
+    pub fn oklab(lightness: f32, a: f32, b: f32) -> Self {
+        Color::from_oklab([lightness, a, b], 1.0)
+    }
+
+    /// Returns the **color** animated in the given color space when it is the target of an animation
+    pub fn interpolated_in(self, interpolation_space: ColorSpace) -> Self {
+        Color {
+            interpolation_space,
+            ..self
+        }
+    }
+
+    /// Returns the **color** with its alpha multiplied by the given **opacity**
+    pub fn with_opacity(&self, opacity: f32) -> Self {
+        Color {
+            a: self.a * opacity,
+            ..*self
+        }
+    }
+
+    /// Returns the hue in degrees, saturation and lightness of the **color**
+    pub fn to_hsl(&self) -> [f32; 3] {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        if chroma <= f32::EPSILON {
+            return [0.0, 0.0, lightness];
+        }
+
+        let hue = if max == self.r {
+            ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            (self.b - self.r) / chroma + 2.0
+        } else {
+            (self.r - self.g) / chroma + 4.0
+        };
+
+        let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+
+        [hue * 60.0, saturation, lightness]
+    }
+
+    /// Returns the OKLab lightness and a and b axes of the **color**
+    // The coefficients are kept exactly as published with OKLab
+    #[allow(clippy::excessive_precision)]
+    pub fn to_oklab(&self) -> [f32; 3] {
+        let [r, g, b] = self.to_linear();
+
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        [
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        ]
+    }
+
+    /// Returns the components as array, as the renderer expects them
+    pub(crate) fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Converts the color components to linear light
+    pub(crate) fn to_linear(self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(srgb_to_linear)
+    }
+
+    /// Creates a **color** from components in linear light
+    fn from_linear([r, g, b]: [f32; 3], a: f32) -> Self {
+        Color::rgba(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a)
+    }
+
+    /// Creates a **color** from OKLab lightness and a and b axes
+    #[allow(clippy::excessive_precision)]
+    fn from_oklab([lightness, a, b]: [f32; 3], alpha: f32) -> Self {
+        let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+        Color::from_linear(
+            [
+                4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+                -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+                -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+            ]
+            .map(|component| component.clamp(0.0, 1.0)),
+            alpha,
+        )
+    }
+}
+
+impl Interpolate for Color {
+    // The color space of the target color is used, so `animate!` can pick it per animation
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        let alpha = self.a.interpolate(&other.a, progress);
+        let lerp = |from: [f32; 3], to: [f32; 3]| {
+            [0, 1, 2].map(|index| from[index].interpolate(&to[index], progress))
+        };
+
+        let color = match other.interpolation_space {
+            ColorSpace::LinearRgb => {
+                Color::from_linear(lerp(self.to_linear(), other.to_linear()), alpha)
+            }
+            ColorSpace::Hsl => {
+                let [mut from_hue, from_saturation, from_lightness] = self.to_hsl();
+                let [mut to_hue, to_saturation, to_lightness] = other.to_hsl();
+
+                // Grays have no hue of their own, they take the hue of the other color like in CSS
+                if from_saturation <= f32::EPSILON {
+                    from_hue = to_hue;
+                }
+                if to_saturation <= f32::EPSILON {
+                    to_hue = from_hue;
+                }
+
+                // Take the shorter way around the hue circle
+                let hue_difference = (to_hue - from_hue + 540.0).rem_euclid(360.0) - 180.0;
+                let [saturation, lightness] = [
+                    from_saturation.interpolate(&to_saturation, progress),
+                    from_lightness.interpolate(&to_lightness, progress),
+                ];
+
+                Color::hsla(
+                    from_hue + hue_difference * progress,
+                    saturation,
+                    lightness,
+                    alpha,
+                )
+            }
+            ColorSpace::OkLab => Color::from_oklab(lerp(self.to_oklab(), other.to_oklab()), alpha),
+        };
+
+        color.interpolated_in(other.interpolation_space)
+    }
+}
+
+/// Converts an sRGB encoded component to linear light
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light component to sRGB encoding
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that the components of two colors differ by less than **tolerance**
+    fn assert_close(actual: Color, expected: Color, tolerance: f32) {
+        let difference = actual
+            .to_array()
+            .iter()
+            .zip(expected.to_array())
+            .map(|(actual, expected)| (actual - expected).abs())
+            .fold(0.0, f32::max);
+
+        assert!(difference < tolerance, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn hex_colors_in_all_lengths() {
+        assert_close(Color::hex("#f80"), Color::rgb(1.0, 0.533, 0.0), 1e-3);
+        assert_close(
+            Color::hex("f808"),
+            Color::rgba(1.0, 0.533, 0.0, 0.533),
+            1e-3,
+        );
+        assert_close(Color::hex("#1e88e5"), Color::rgb(0.118, 0.533, 0.898), 1e-3);
+        assert_close(
+            Color::hex("#1e88e580"),
+            Color::rgba(0.118, 0.533, 0.898, 0.502),
+            1e-3,
+        );
+    }
+
+    #[test]
+    fn invalid_hex_colors() {
+        for hex in ["", "#", "#12", "#12345", "#1234567", "#gggggg", "#1e88é5"] {
+            assert_eq!(Color::try_hex(hex), None, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for color in [
+            Color::RED,
+            Color::TEAL,
+            Color::PURPLE,
+            Color::GRAY,
+            Color::WHITE,
+        ] {
+            let [hue, saturation, lightness] = color.to_hsl();
+            assert_close(Color::hsl(hue, saturation, lightness), color, 1e-4);
+        }
+
+        assert_close(Color::hsl(120.0, 1.0, 0.5), Color::rgb(0.0, 1.0, 0.0), 1e-6);
+    }
+
+    #[test]
+    fn oklab_round_trip() {
+        for color in [
+            Color::RED,
+            Color::TEAL,
+            Color::PURPLE,
+            Color::GRAY,
+            Color::WHITE,
+        ] {
+            let [lightness, a, b] = color.to_oklab();
+            assert_close(Color::oklab(lightness, a, b), color, 1e-4);
+        }
+
+        // White has no chroma
+        let [lightness, a, b] = Color::WHITE.to_oklab();
+        assert!((lightness - 1.0).abs() < 1e-4 && a.abs() < 1e-4 && b.abs() < 1e-4);
+    }
+
+    #[test]
+    fn interpolation_ends_at_both_colors_in_every_space() {
+        for space in [ColorSpace::LinearRgb, ColorSpace::Hsl, ColorSpace::OkLab] {
+            let target = Color::BLUE.interpolated_in(space);
+
+            assert_close(Color::RED.interpolate(&target, 0.0), Color::RED, 1e-4);
+            assert_close(Color::RED.interpolate(&target, 1.0), Color::BLUE, 1e-4);
+            assert_eq!(
+                Color::RED.interpolate(&target, 0.5).interpolation_space,
+                space
+            );
+        }
+    }
+
+    #[test]
+    fn linear_interpolation_mixes_light() {
+        let middle = Color::BLACK.interpolate(&Color::WHITE, 0.5);
+
+        // Half the light is brighter than half the sRGB value
+        assert_close(middle, Color::rgb(0.735, 0.735, 0.735), 1e-3);
+    }
+
+    #[test]
+    fn hsl_interpolation_takes_the_shorter_way_around() {
+        let target = Color::hsl(340.0, 1.0, 0.5).interpolated_in(ColorSpace::Hsl);
+        let [hue, _, _] = Color::hsl(20.0, 1.0, 0.5)
+            .interpolate(&target, 0.5)
+            .to_hsl();
+
+        assert!(!(1e-2..=360.0 - 1e-2).contains(&hue), "{}", hue);
+    }
+
+    #[test]
+    fn hsl_interpolation_from_gray_keeps_the_hue() {
+        let target = Color::BLUE.interpolated_in(ColorSpace::Hsl);
+        let [blue_hue, _, _] = Color::BLUE.to_hsl();
+
+        for from in [Color::WHITE, Color::BLACK, Color::GRAY] {
+            let [hue, _, _] = from.interpolate(&target, 0.5).to_hsl();
+            assert!((hue - blue_hue).abs() < 1e-2, "{} != {}", hue, blue_hue);
+        }
+
+        let [hue, _, _] = Color::BLUE
+            .interpolate(&Color::WHITE.interpolated_in(ColorSpace::Hsl), 0.5)
+            .to_hsl();
+        assert!((hue - blue_hue).abs() < 1e-2);
+    }
+
+    #[test]
+    fn oklab_interpolation_keeps_the_middle_bright() {
+        let middle =
+            Color::BLACK.interpolate(&Color::WHITE.interpolated_in(ColorSpace::OkLab), 0.5);
+        let [lightness, _, _] = middle.to_oklab();
+
+        assert!((lightness - 0.5).abs() < 1e-3);
+    }
+}
@@ -1,120 +1,511 @@
-use crate::element::Element;
-use crate::renderer::{Vertex, WithId};
-use crate::{element, Easing, Keyframe};
-use lyon::lyon_tessellation::VertexBuffers;
-use lyon::math::point;
+use crate::animation::Interpolate;
+use crate::element::measure::MeasuredPath;
+use lyon::math::{point, vector, Angle, Point, Rotation, Vector};
 use lyon::path::Path;
-use lyon::tessellation::{
-    BuffersBuilder, FillOptions, FillRule, FillTessellator, LineCap, StrokeOptions,
-    StrokeTessellator,
-};
-use std::borrow::{Borrow, BorrowMut};
-use std::mem;
-
-#[derive(Clone)]
-pub struct Arrow {
-    pub x: f32,
-    pub y: f32,
-    pub stroke: bool,
-    pub fill: bool,
-    pub scale: f32,
-    pub angle: f32,
-    pub(crate) keyframes: Vec<Keyframe<Arrow>>,
-    pub(crate) id: usize,
+
+/// Maximum number of segments an arc is flattened into, arcs turning around many times are drawn coarser
+const MAX_ARC_SEGMENTS: u32 = 256;
+
+/// Shape of the tips of an **Arrow**
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowTip {
+    /// A filled triangle
+    Triangle,
+    /// Two lines as wide as the shaft, meeting at the tip
+    Open,
+    /// A triangle with its back notched in, like a stealth bomber
+    Stealth,
 }
 
-impl Arrow {
-    /// Add keyframes to the given **Element**, this method is automatically invoked by the `animate!` macro!
-    pub fn add_keyframe(
-        &mut self,
-        keyframe_data: (fn(&mut Arrow, f32), f32, f32, f32, f32, Easing),
-    ) {
-        let (setter_fn, start_value, end_value, start_time, duration, easing) = keyframe_data;
-
-        self.keyframes.push(Keyframe {
-            setter_fn,
-            start_value,
-            end_value,
-            start_time,
-            duration,
-            easing,
-        });
+/// Course of an **Arrow** from its start to its end point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrowCurve {
+    /// A straight line
+    Straight,
+    /// A circular arc spanning the given **angle** in radians, positive angles turn counter clockwise
+    Arc { angle: f32 },
+    /// A quadratic bezier curve bent towards the **control** point, relative to the origin of the arrow
+    Bezier { control: [f32; 2] },
+}
+
+shape! {
+    /// An **Arrow** from a start to an end point, both relative to the origin of the element
+    ///
+    /// Animate the end point from the start point to its target to grow the arrow from its tail.
+    pub struct Arrow {
+        /// Horizontal position of the tail
+        pub start_x: f32 = -1.0,
+        /// Vertical position of the tail
+        pub start_y: f32 = 0.0,
+        /// Horizontal position of the tip
+        pub end_x: f32 = 1.0,
+        /// Vertical position of the tip
+        pub end_y: f32 = 0.0,
+        /// Width of the shaft, at most as wide as the tip if there is one, open tips are drawn with the same width
+        pub shaft_width: f32 = 0.4,
+        /// Length of the tip along the arrow, shortened if the arrow is too short for its tips
+        pub tip_length: f32 = 1.0,
+        /// Width of the tip across the arrow
+        pub tip_width: f32 = 1.6,
+        /// Shape of the tips
+        pub tip_style: ArrowTip = ArrowTip::Triangle,
+        /// Whether the tail has a tip as well
+        pub double_headed: bool = false,
+        /// Course of the shaft from the tail to the tip
+        pub curve: ArrowCurve = ArrowCurve::Straight,
     }
 }
 
-impl crate::element::private::Element for Arrow {
-    fn render(&self, geometry: &mut VertexBuffers<Vertex, u16>, primitive_id: usize) {
-        // Build a Path for the arrow
+impl crate::element::private::Shape for Arrow {
+    fn build_path(&self, tolerance: f32) -> Path {
+        let (start, end) = (
+            point(self.start_x, self.start_y),
+            point(self.end_x, self.end_y),
+        );
+        let centerline = MeasuredPath::new(&self.build_centerline(start, end), tolerance);
+        let length = centerline.length();
+
+        // Tips take up at most the whole length of the arrow
+        let heads = if self.double_headed { 2.0 } else { 1.0 };
+        let tip_length = self.tip_length.clamp(0.0, length / heads);
+        let half_tip_width = self.tip_width.max(0.0) / 2.0;
+        let half_shaft_width = if tip_length > 0.0 {
+            (self.shaft_width.max(0.0) / 2.0).min(half_tip_width)
+        } else {
+            // Without tips the shaft is all there is, its end is cut square
+            self.shaft_width.max(0.0) / 2.0
+        };
+
+        let shaft_start = if self.double_headed { tip_length } else { 0.0 };
+        let shaft_end = length - tip_length;
+        let shaft = centerline.polyline(shaft_start, shaft_end);
+
+        // Tips point along the chord from their base to the endpoint, so the shaft joins them exactly. Ends without tips
+        // follow the last piece of the shaft instead.
+        let tip_frame = |apex: Point, base: f32, neighbor: Option<&Point>| {
+            if tip_length > 0.0 {
+                TipFrame::new(apex, centerline.point_at(base))
+            } else {
+                TipFrame::new(apex, neighbor.copied().unwrap_or(apex))
+            }
+        };
+        let end_tip = tip_frame(end, shaft_end, shaft.iter().rev().nth(1));
+        let start_tip = self
+            .double_headed
+            .then(|| tip_frame(start, shaft_start, shaft.get(1)));
+
+        let tip_outline = |frame: &TipFrame| {
+            if tip_length <= 0.0 {
+                return vec![];
+            }
+
+            tip_outline(
+                self.tip_style,
+                frame.length,
+                half_shaft_width,
+                half_tip_width,
+            )
+            .into_iter()
+            .map(|local| frame.to_world(local))
+            .collect::<Vec<_>>()
+        };
+
+        // The shaft ends square, its last normals follow the tips
+        let first_normal = match &start_tip {
+            Some(frame) => -frame.normal(),
+            None => segment_normal(&shaft, 0).unwrap_or_else(|| end_tip.normal()),
+        };
+        let normals = offset_normals(&shaft, first_normal, end_tip.normal());
+
+        let left = shaft
+            .iter()
+            .zip(&normals)
+            .map(|(point, normal)| *point + *normal * half_shaft_width);
+        let right = shaft
+            .iter()
+            .zip(&normals)
+            .map(|(point, normal)| *point - *normal * half_shaft_width)
+            .rev();
+
+        // One outline along the left side, around the tip, back along the right side and around the tail
+        let mut outline: Vec<Point> = left.collect();
+        outline.extend(tip_outline(&end_tip));
+        outline.extend(right);
+        if let Some(frame) = &start_tip {
+            outline.extend(tip_outline(frame));
+        }
+
         let mut builder = Path::builder();
-        builder.begin(point(-1.0, -0.2));
-        builder.line_to(point(0.0, -0.2));
-        builder.line_to(point(0.0, -0.8));
-        builder.line_to(point(1.0, 0.0));
-        builder.line_to(point(0.0, 0.8));
-        builder.line_to(point(0.0, 0.2));
-        builder.line_to(point(-1.0, 0.2));
+        builder.begin(outline[0]);
+        outline[1..].iter().for_each(|point| {
+            builder.line_to(*point);
+        });
         builder.close();
-        let arrow_path = builder.build();
+        builder.build()
+    }
+}
+
+impl Arrow {
+    /// Builds the line the arrow follows from its tail to its tip
+    fn build_centerline(&self, start: Point, end: Point) -> Path {
+        let mut builder = Path::builder();
+        builder.begin(start);
+
+        match self.curve {
+            ArrowCurve::Straight => {
+                builder.line_to(end);
+            }
+            ArrowCurve::Arc { angle }
+                if angle.is_finite() && angle.abs() > f32::EPSILON && start != end =>
+            {
+                // The center lies on the perpendicular bisector of the chord
+                let chord = end - start;
+                let center =
+                    start.lerp(end, 0.5) + vector(-chord.y, chord.x) / 2.0 / (angle / 2.0).tan();
+
+                let segments = ((angle.abs() / (std::f32::consts::PI / 64.0)).ceil() as u32)
+                    .min(MAX_ARC_SEGMENTS);
+                (1..=segments).for_each(|segment| {
+                    let rotation =
+                        Rotation::new(Angle::radians(angle * segment as f32 / segments as f32));
+                    builder.line_to(center + rotation.transform_vector(start - center));
+                });
+            }
+            ArrowCurve::Arc { .. } => {
+                builder.line_to(end);
+            }
+            ArrowCurve::Bezier { control } => {
+                builder.quadratic_bezier_to(point(control[0], control[1]), end);
+            }
+        }
+
+        builder.end(false);
+        builder.build()
+    }
+}
+
+/// Position and direction of a tip, the tip points from the **base** to the **apex**
+struct TipFrame {
+    apex: Point,
+    direction: Vector,
+    length: f32,
+}
+
+impl TipFrame {
+    fn new(apex: Point, base: Point) -> Self {
+        let chord = apex - base;
+
+        TipFrame {
+            apex,
+            direction: chord.try_normalize().unwrap_or(vector(1.0, 0.0)),
+            length: chord.length(),
+        }
+    }
+
+    /// Normal pointing to the left of the direction
+    fn normal(&self) -> Vector {
+        vector(-self.direction.y, self.direction.x)
+    }
+
+    /// Maps a point relative to the apex, with x pointing forward and y to the left
+    fn to_world(&self, local: Point) -> Point {
+        self.apex + self.direction * local.x + self.normal() * local.y
+    }
+}
 
-        // Set custom tolerance
-        let tolerance = 0.02;
+/// Returns the outline of a tip from the left side of the shaft around the apex to its right side
+///
+/// The apex is at the origin, the tip points towards positive x and its base is at `-length`.
+fn tip_outline(
+    style: ArrowTip,
+    length: f32,
+    half_shaft_width: f32,
+    half_tip_width: f32,
+) -> Vec<Point> {
+    let left = match style {
+        ArrowTip::Triangle => vec![point(-length, half_tip_width), point(0.0, 0.0)],
+        ArrowTip::Stealth => {
+            // The back edges run from the wings to a notch on the shaft, a third into the tip
+            let notch = length / 3.0;
+            let join =
+                -length + notch * (1.0 - half_shaft_width / half_tip_width.max(f32::EPSILON));
 
-        if self.fill {
-            let mut fill_tess = FillTessellator::new();
+            vec![
+                point(join, half_shaft_width),
+                point(-length, half_tip_width),
+                point(0.0, 0.0),
+            ]
+        }
+        ArrowTip::Open => {
+            // The lines of the tip are offset to both sides of the edges of a triangle tip
+            let edge = vector(-length, half_tip_width);
+            let edge_length = edge.length().max(f32::EPSILON);
+            let outward = vector(half_tip_width, length) / edge_length * half_shaft_width;
+
+            // The outer edges meet in front of the apex, the whole tip is moved back so they meet at the endpoint
+            let shift = half_shaft_width * edge_length / half_tip_width.max(f32::EPSILON);
+
+            // The inner edge meets the side of the shaft
+            let inner = point(0.0, 0.0) - outward;
+            let inner_join =
+                inner.x + (half_shaft_width - inner.y) / edge.y.max(f32::EPSILON) * edge.x;
 
-            fill_tess
-                .tessellate_path(
-                    &arrow_path,
-                    &FillOptions::tolerance(tolerance).with_fill_rule(FillRule::NonZero),
-                    &mut BuffersBuilder::new(geometry, WithId(primitive_id as u32)),
-                )
-                .unwrap();
+            vec![
+                point((inner_join - shift).max(-length), half_shaft_width),
+                point(-length, half_tip_width) - outward - vector(shift, 0.0),
+                point(-length, half_tip_width) + outward - vector(shift, 0.0),
+                point(0.0, 0.0),
+            ]
         }
+    };
+
+    // The right side mirrors the left side, the apex is shared
+    let right = left
+        .iter()
+        .rev()
+        .skip(1)
+        .map(|local| point(local.x, -local.y));
+
+    left.iter().copied().chain(right).collect()
+}
+
+/// Returns the left normal of the segment starting at **index**, `None` if there is no such segment or it has no length
+fn segment_normal(points: &[Point], index: usize) -> Option<Vector> {
+    let direction = (*points.get(index + 1)? - *points.get(index)?).try_normalize()?;
+
+    Some(vector(-direction.y, direction.x))
+}
+
+/// Returns the offset directions of a polyline, scaled so both sides keep their distance at corners
+fn offset_normals(points: &[Point], first: Vector, last: Vector) -> Vec<Vector> {
+    (0..points.len())
+        .map(|index| {
+            if index == 0 {
+                return first;
+            }
+
+            if index == points.len() - 1 {
+                return last;
+            }
 
-        if self.stroke {
-            let mut stroke_tess = StrokeTessellator::new();
-
-            stroke_tess
-                .tessellate_path(
-                    &arrow_path,
-                    &StrokeOptions::tolerance(tolerance)
-                        .with_line_width(0.05)
-                        .with_line_cap(LineCap::Round),
-                    &mut BuffersBuilder::new(geometry, WithId(primitive_id as u32)),
-                )
-                .unwrap();
+            let after = segment_normal(points, index).unwrap_or(last);
+            let before = segment_normal(points, index - 1).unwrap_or(after);
+            let miter = (before + after).try_normalize().unwrap_or(after);
+
+            // Sharp corners would offset too far, their miter is limited
+            miter / miter.dot(after).max(0.5)
+        })
+        .collect()
+}
+
+impl Interpolate for ArrowCurve {
+    // A straight arrow is an arc without angle, so it can bend into one
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        let angle = |curve: &ArrowCurve| match curve {
+            ArrowCurve::Straight => Some(0.0),
+            ArrowCurve::Arc { angle } => Some(*angle),
+            ArrowCurve::Bezier { .. } => None,
+        };
+
+        match (self, other) {
+            (
+                ArrowCurve::Bezier { control },
+                ArrowCurve::Bezier {
+                    control: other_control,
+                },
+            ) => ArrowCurve::Bezier {
+                control: [0, 1]
+                    .map(|index| control[index].interpolate(&other_control[index], progress)),
+            },
+            _ => match (angle(self), angle(other)) {
+                (Some(from), Some(to)) if progress < 1.0 => ArrowCurve::Arc {
+                    angle: from.interpolate(&to, progress),
+                },
+                _ if progress < 1.0 => *self,
+                _ => *other,
+            },
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::private::Shape as _;
+    use lyon::path::PathEvent;
+
+    /// Returns the points of all subpaths of the **path**
+    fn points(path: &Path) -> Vec<Point> {
+        path.iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(at),
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .collect()
+    }
 
-    fn box_clone(&self) -> Box<dyn crate::element::Element + Send> {
-        Box::new((*self).clone())
+    /// Returns the x coordinates of the outline points of the **arrow** on the outer corners of its tips
+    fn wing_positions(arrow: &Arrow) -> Vec<f32> {
+        let mut positions: Vec<f32> = points(&arrow.build_path(0.01))
+            .into_iter()
+            .filter(|point| (point.y.abs() - arrow.tip_width / 2.0).abs() < 1e-5)
+            .map(|point| point.x)
+            .collect();
+        positions.sort_by(f32::total_cmp);
+        positions.dedup_by(|a, b| (*a - *b).abs() < 1e-5);
+
+        positions
     }
 
-    fn update_data_with_keyframes(&mut self, time_since_start: f32) {
-        // Evaluate which keyframes need to be updated and update them
-        let keyframes = mem::take(&mut self.keyframes);
+    #[test]
+    fn straight_arrows_end_at_the_end_point() {
+        let arrow = Arrow {
+            start_x: -2.0,
+            start_y: 1.0,
+            end_x: 3.0,
+            end_y: -1.0,
+            ..Arrow::default()
+        };
+        let outline = points(&arrow.build_path(0.01));
 
-        keyframes
+        assert!(outline
             .iter()
-            .filter(|keyframe| keyframe.is_active(time_since_start))
-            .for_each(|keyframe| {
-                keyframe.update_keyframe_data(self.borrow_mut(), time_since_start)
-            });
+            .any(|point| (*point - lyon::math::point(3.0, -1.0)).length() < 1e-5));
 
-        self.keyframes = keyframes;
+        // Nothing reaches beyond the apex along the arrow
+        let direction = vector(5.0, -2.0).normalize();
+        let reach = outline
+            .iter()
+            .map(|point| (*point - lyon::math::point(-2.0, 1.0)).dot(direction))
+            .fold(f32::MIN, f32::max);
+        assert!((reach - vector(5.0, -2.0).length()).abs() < 1e-4);
     }
 
-    fn get_scale(&self) -> f32 {
-        self.scale
+    #[test]
+    fn double_headed_arrows_split_the_length_between_their_tips() {
+        let arrow = Arrow {
+            tip_length: 5.0,
+            ..Arrow::default()
+        };
+        assert_eq!(wing_positions(&arrow), vec![-1.0]);
+
+        let double_headed = Arrow {
+            double_headed: true,
+            ..arrow
+        };
+        assert_eq!(wing_positions(&double_headed), vec![0.0]);
     }
 
-    fn get_angle(&self) -> f32 {
-        self.angle
+    #[test]
+    fn short_arrows_shorten_their_tips() {
+        let arrow = Arrow {
+            start_x: 0.0,
+            end_x: 0.5,
+            tip_length: 1.0,
+            ..Arrow::default()
+        };
+
+        assert_eq!(wing_positions(&arrow), vec![0.0]);
     }
-}
 
-impl Element for Arrow {
-    fn get_id(&self) -> usize {
-        self.id
+    #[test]
+    fn arrows_without_tips_keep_their_shaft() {
+        let arrow = Arrow {
+            tip_length: 0.0,
+            tip_width: 0.0,
+            shaft_width: 0.2,
+            ..Arrow::default()
+        };
+        let outline = points(&arrow.build_path(0.01));
+        let height = outline
+            .iter()
+            .map(|point| point.y.abs())
+            .fold(0.0, f32::max);
+
+        assert!((height - 0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn arrows_without_tips_end_square() {
+        // A tip width without length leaves no wings, the ends are cut across the shaft
+        let arrow = Arrow {
+            start_x: 0.0,
+            start_y: -1.0,
+            end_x: 0.0,
+            end_y: 1.0,
+            tip_length: 0.0,
+            tip_width: 1.0,
+            shaft_width: 0.2,
+            double_headed: true,
+            ..Arrow::default()
+        };
+        let outline = points(&arrow.build_path(0.01));
+
+        assert!(outline
+            .iter()
+            .all(|point| (point.x.abs() - 0.1).abs() < 1e-5 && point.y.abs() <= 1.0 + 1e-5));
+    }
+
+    #[test]
+    fn arcs_end_exactly_at_the_end_point() {
+        for angle in [0.5, -2.0, 3.0] {
+            let arrow = Arrow {
+                start_x: -1.0,
+                start_y: 0.5,
+                end_x: 2.0,
+                end_y: -0.5,
+                curve: ArrowCurve::Arc { angle },
+                ..Arrow::default()
+            };
+            let centerline = points(
+                &arrow.build_centerline(lyon::math::point(-1.0, 0.5), lyon::math::point(2.0, -0.5)),
+            );
+
+            assert!(centerline.len() > 2);
+            assert!((*centerline.last().unwrap() - lyon::math::point(2.0, -0.5)).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn arcs_are_split_into_a_limited_number_of_segments() {
+        let (start, end) = (lyon::math::point(-1.0, 0.0), lyon::math::point(1.0, 0.0));
+        let centerline = |angle: f32| {
+            let arrow = Arrow {
+                curve: ArrowCurve::Arc { angle },
+                ..Arrow::default()
+            };
+            points(&arrow.build_centerline(start, end))
+        };
+
+        assert_eq!(centerline(1e6).len(), MAX_ARC_SEGMENTS as usize + 1);
+        assert_eq!(centerline(f32::INFINITY), vec![start, end]);
+        assert_eq!(centerline(f32::NAN), vec![start, end]);
+    }
+
+    #[test]
+    fn straight_arrows_bend_into_arcs() {
+        let arc = ArrowCurve::Arc { angle: 2.0 };
+
+        assert_eq!(
+            ArrowCurve::Straight.interpolate(&arc, 0.5),
+            ArrowCurve::Arc { angle: 1.0 }
+        );
+        assert_eq!(ArrowCurve::Straight.interpolate(&arc, 1.0), arc);
+        assert_eq!(
+            arc.interpolate(&ArrowCurve::Straight, 0.75),
+            ArrowCurve::Arc { angle: 0.5 }
+        );
+        assert_eq!(
+            arc.interpolate(&ArrowCurve::Straight, 1.0),
+            ArrowCurve::Straight
+        );
+
+        // Bezier curves can't be blended with arcs, they switch at the end
+        let bezier = ArrowCurve::Bezier {
+            control: [0.0, 1.0],
+        };
+        assert_eq!(arc.interpolate(&bezier, 0.5), arc);
+        assert_eq!(arc.interpolate(&bezier, 1.0), bezier);
     }
 }
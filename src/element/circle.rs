@@ -0,0 +1,19 @@
+use lyon::math::point;
+use lyon::path::traits::PathBuilder;
+use lyon::path::{Path, Winding};
+
+shape! {
+    /// A **Circle** around the origin of the element
+    pub struct Circle {
+        /// Radius of the circle
+        pub radius: f32 = 1.0,
+    }
+}
+
+impl crate::element::private::Shape for Circle {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let mut builder = Path::builder();
+        builder.add_circle(point(0.0, 0.0), self.radius.max(0.0), Winding::Positive);
+        builder.build()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::element::{polylines_to_path, sample_curve};
+use lyon::math::point;
+use lyon::path::Path;
+use std::f32::consts::TAU;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+shape! {
+    /// A **ParametricCurve** through the points `(x(t), y(t))` for **t** from **t_min** to **t_max**
+    ///
+    /// Animate **t_max** from **t_min** to trace the curve out. The curve is sampled more densely where it bends, so it stays
+    /// within the tessellation tolerance, and it is interrupted where the function isn't finite.
+    pub struct ParametricCurve {
+        /// Maps the parameter to a point relative to the origin of the element
+        function: Arc<dyn Fn(f32) -> (f32, f32) + Send + Sync> = Arc::new(|t| (t, 0.0)),
+        /// Parameter at the start of the curve
+        pub t_min: f32 = 0.0,
+        /// Parameter at the end of the curve
+        pub t_max: f32 = 1.0,
+    }
+}
+
+impl ParametricCurve {
+    /// Creates a curve along the **function** over the given parameter **range**, like `|t| (t.cos(), t.sin())` for a circle
+    pub fn new(
+        function: impl Fn(f32) -> (f32, f32) + Send + Sync + 'static,
+        range: RangeInclusive<f32>,
+    ) -> Self {
+        ParametricCurve {
+            function: Arc::new(function),
+            t_min: *range.start(),
+            t_max: *range.end(),
+            ..ParametricCurve::default()
+        }
+    }
+}
+
+impl crate::element::private::Shape for ParametricCurve {
+    fn build_path(&self, tolerance: f32) -> Path {
+        let polylines = sample_curve(
+            |t| {
+                let (x, y) = (self.function)(t);
+                point(x, y)
+            },
+            self.t_min,
+            self.t_max,
+            tolerance,
+        );
+
+        polylines_to_path(&polylines)
+    }
+}
+
+shape! {
+    /// A **PolarCurve** at the distance `r(θ)` from the origin for angles **θ** from **theta_min** to **theta_max**
+    ///
+    /// Angles are in radians and count counter clockwise from the x axis, negative distances point the opposite way.
+    /// Animate **theta_max** to trace the curve out, it's sampled like a **ParametricCurve**.
+    pub struct PolarCurve {
+        /// Maps the angle to the distance from the origin of the element
+        function: Arc<dyn Fn(f32) -> f32 + Send + Sync> = Arc::new(|_| 1.0),
+        /// Angle at the start of the curve
+        pub theta_min: f32 = 0.0,
+        /// Angle at the end of the curve
+        pub theta_max: f32 = TAU,
+    }
+}
+
+impl PolarCurve {
+    /// Creates a curve along the **function** over the given **range** of angles, like `|theta| (3.0 * theta).cos()` for a rose
+    pub fn new(
+        function: impl Fn(f32) -> f32 + Send + Sync + 'static,
+        range: RangeInclusive<f32>,
+    ) -> Self {
+        PolarCurve {
+            function: Arc::new(function),
+            theta_min: *range.start(),
+            theta_max: *range.end(),
+            ..PolarCurve::default()
+        }
+    }
+}
+
+impl crate::element::private::Shape for PolarCurve {
+    fn build_path(&self, tolerance: f32) -> Path {
+        let polylines = sample_curve(
+            |theta| {
+                let radius = (self.function)(theta);
+                point(radius * theta.cos(), radius * theta.sin())
+            },
+            self.theta_min,
+            self.theta_max,
+            tolerance,
+        );
+
+        polylines_to_path(&polylines)
+    }
+}
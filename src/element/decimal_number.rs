@@ -0,0 +1,158 @@
+use crate::text::{layout, Font, LayoutCache, LayoutKey, LayoutOptions, TextAlign};
+use lyon::path::Path;
+
+/// Most digits shown after the decimal separator, more are beyond the precision of the value anyway
+const MAX_DECIMAL_PLACES: u32 = 20;
+
+shape! {
+    /// A **DecimalNumber** shows a formatted **value**, animate the value to count up or down
+    ///
+    /// The number is laid out again whenever its text changes, with **tabular_digits** and right alignment the digits stay in place while the value changes.
+    pub struct DecimalNumber {
+        /// Value to show
+        pub value: f32 = 0.0,
+        /// Number of digits after the decimal separator, the value is rounded to them, at most 20 are shown
+        pub decimal_places: u32 = 2,
+        /// Separator between the integer and the fractional digits
+        pub decimal_separator: char = '.',
+        /// Separator between groups of three integer digits, `None` to not group them
+        pub thousands_separator: Option<char> = None,
+        /// Text in front of the number, like a currency symbol
+        pub prefix: String = String::new(),
+        /// Text after the number, like a unit
+        pub suffix: String = String::new(),
+        /// Font the glyphs are taken from, the bundled DejaVu Sans by default
+        pub font: Font = Font::default(),
+        /// Height of the em square in scene units
+        pub font_size: f32 = 0.5,
+        /// Alignment of the number relative to the origin, which is on its baseline
+        pub align: TextAlign = TextAlign::Left,
+        /// Whether all digits take the width of the widest one, so the number doesn't jitter while it changes
+        pub tabular_digits: bool = true,
+        /// Outline of the last text shown, so a value that doesn't change the text is not laid out again
+        pub(crate) laid_out: LayoutCache<Path> = LayoutCache::default(),
+    }
+}
+
+impl DecimalNumber {
+    /// Returns the text shown for the current **value**, including prefix and suffix
+    pub fn formatted(&self) -> String {
+        let value = if self.value.is_finite() {
+            self.value
+        } else {
+            0.0
+        };
+        let decimal_places = self.decimal_places.min(MAX_DECIMAL_PLACES);
+        let digits = format!("{:.*}", decimal_places as usize, value.abs());
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        // Values rounded to zero drop their sign
+        let negative = value < 0.0
+            && digits
+                .chars()
+                .any(|digit| digit.is_ascii_digit() && digit != '0');
+
+        // The sign goes in front of the prefix, like in -$5
+        let mut text = String::from(if negative { "-" } else { "" });
+        text.push_str(&self.prefix);
+
+        for (index, digit) in integer.chars().enumerate() {
+            let remaining = integer.len() - index;
+            if index > 0 && remaining % 3 == 0 {
+                text.extend(self.thousands_separator);
+            }
+            text.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            text.push(self.decimal_separator);
+            text.push_str(fraction);
+        }
+
+        text.push_str(&self.suffix);
+        text
+    }
+}
+
+impl crate::element::private::Shape for DecimalNumber {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let options = LayoutOptions {
+            font_size: self.font_size.max(0.0),
+            line_height: 1.2,
+            align: self.align,
+            max_width: None,
+            indent: 0.0,
+            tabular_digits: self.tabular_digits,
+        };
+
+        let key = LayoutKey {
+            text: self.formatted(),
+            font: self.font.clone(),
+            options,
+        };
+        let path = self.laid_out.get_or_build(key, |key| {
+            layout(&key.font, &key.text, &key.options).build_path(&key.font)
+        });
+
+        path.as_ref().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(value: f32, decimal_places: u32) -> DecimalNumber {
+        DecimalNumber {
+            value,
+            decimal_places,
+            ..DecimalNumber::default()
+        }
+    }
+
+    #[test]
+    fn values_are_rounded_to_the_decimal_places() {
+        assert_eq!(number(1.236, 2).formatted(), "1.24");
+        assert_eq!(number(2.7, 0).formatted(), "3");
+        assert_eq!(number(-1.5, 3).formatted(), "-1.500");
+        assert_eq!(number(f32::NAN, 1).formatted(), "0.0");
+    }
+
+    #[test]
+    fn values_rounded_to_zero_drop_their_sign() {
+        assert_eq!(number(-0.0, 2).formatted(), "0.00");
+        assert_eq!(number(-0.001, 2).formatted(), "0.00");
+        assert_eq!(number(-0.01, 2).formatted(), "-0.01");
+    }
+
+    #[test]
+    fn integer_digits_are_grouped_by_thousands() {
+        let grouped = |value: f32, decimal_places: u32| DecimalNumber {
+            thousands_separator: Some(','),
+            ..number(value, decimal_places)
+        };
+
+        assert_eq!(grouped(1234567.0, 0).formatted(), "1,234,567");
+        assert_eq!(grouped(-1234.5, 2).formatted(), "-1,234.50");
+        assert_eq!(grouped(123.0, 0).formatted(), "123");
+    }
+
+    #[test]
+    fn the_sign_goes_in_front_of_the_prefix() {
+        let price = DecimalNumber {
+            prefix: "$".to_string(),
+            suffix: " total".to_string(),
+            decimal_separator: ',',
+            ..number(-5.25, 2)
+        };
+
+        assert_eq!(price.formatted(), "-$5,25 total");
+    }
+
+    #[test]
+    fn decimal_places_are_limited() {
+        let formatted = number(1.0, u32::MAX).formatted();
+
+        assert_eq!(formatted.len(), 2 + MAX_DECIMAL_PLACES as usize);
+    }
+}
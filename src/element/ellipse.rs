@@ -0,0 +1,26 @@
+use lyon::math::{point, vector, Angle};
+use lyon::path::traits::PathBuilder;
+use lyon::path::{Path, Winding};
+
+shape! {
+    /// An axis aligned **Ellipse** around the origin of the element, rotate the element to tilt it
+    pub struct Ellipse {
+        /// Horizontal radius of the ellipse
+        pub radius_x: f32 = 1.0,
+        /// Vertical radius of the ellipse
+        pub radius_y: f32 = 0.5,
+    }
+}
+
+impl crate::element::private::Shape for Ellipse {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let mut builder = Path::builder();
+        builder.add_ellipse(
+            point(0.0, 0.0),
+            vector(self.radius_x.max(0.0), self.radius_y.max(0.0)),
+            Angle::zero(),
+            Winding::Positive,
+        );
+        builder.build()
+    }
+}
@@ -0,0 +1,44 @@
+use crate::element::{Element, Style};
+use crate::renderer::Canvas;
+use lyon::math::Transform;
+
+element! {
+    /// A **Group** of elements, the children inherit the transform and opacity of the group
+    pub struct Group {
+        /// Ids of the children
+        pub(crate) children: Vec<usize> = vec![],
+        /// Snapshots of children that are added to the scene along with the group
+        pub(crate) pending_children: Vec<Box<dyn Element + Send>> = vec![],
+    }
+}
+
+impl Group {
+    /// Adds an **element** to the group, the element may itself be a group
+    ///
+    /// Children that are not added to the scene on their own are added along with the group, animating them later on is still possible.
+    pub fn add(&mut self, element_reference: &dyn Element) {
+        let id = element_reference.get_id();
+
+        // A group can't contain itself and children are only tracked once
+        if id == self.id || self.children.contains(&id) {
+            return;
+        }
+
+        self.children.push(id);
+        self.pending_children.push(element_reference.box_clone());
+    }
+}
+
+impl crate::element::private::Node for Group {
+    fn render(&self, _canvas: &mut Canvas, _transform: &Transform, _style: &Style) {
+        // A group has no geometry of its own, the children are rendered on their own
+    }
+
+    fn get_children(&self) -> &[usize] {
+        &self.children
+    }
+
+    fn take_pending_children(&mut self) -> Vec<Box<dyn Element + Send>> {
+        std::mem::take(&mut self.pending_children)
+    }
+}
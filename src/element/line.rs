@@ -0,0 +1,26 @@
+use lyon::math::point;
+use lyon::path::Path;
+
+shape! {
+    /// A straight **Line** between two points relative to the origin of the element, it is drawn by its stroke
+    pub struct Line {
+        /// Horizontal position of the start point
+        pub start_x: f32 = -1.0,
+        /// Vertical position of the start point
+        pub start_y: f32 = 0.0,
+        /// Horizontal position of the end point
+        pub end_x: f32 = 1.0,
+        /// Vertical position of the end point
+        pub end_y: f32 = 0.0,
+    }
+}
+
+impl crate::element::private::Shape for Line {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let mut builder = Path::builder();
+        builder.begin(point(self.start_x, self.start_y));
+        builder.line_to(point(self.end_x, self.end_y));
+        builder.end(false);
+        builder.build()
+    }
+}
@@ -0,0 +1,302 @@
+/// Defines an **element** struct together with the fields and trait implementations shared by all elements
+///
+/// Every element gets a transform (position, anchor, rotation, scale and skew), `opacity` and `visible` on top of its own
+/// fields, which are declared with a default value. The element specific behaviour is implemented by hand
+/// through the `private::Node` trait.
+macro_rules! element {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident: $field_type:ty = $field_default:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        // `Clone` keeps the **id**, so a clone is the same element at another point in time, use `duplicate` for a new element
+        #[derive(Clone)]
+        pub struct $name {
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: $field_type,
+            )*
+            /// Position on the x axis
+            pub x: f32,
+            /// Position on the y axis
+            pub y: f32,
+            /// Pivot for rotation, scale and skew on the x axis, in local coordinates
+            pub anchor_x: f32,
+            /// Pivot for rotation, scale and skew on the y axis, in local coordinates
+            pub anchor_y: f32,
+            /// Rotation in radians
+            pub angle: f32,
+            /// Uniform scale, multiplied with `scale_x` and `scale_y`
+            pub scale: f32,
+            /// Scale along the x axis
+            pub scale_x: f32,
+            /// Scale along the y axis
+            pub scale_y: f32,
+            /// Skew angle along the x axis in radians
+            pub skew_x: f32,
+            /// Skew angle along the y axis in radians
+            pub skew_y: f32,
+            /// Opacity between 0 and 1, multiplied with the opacity of all parent groups
+            pub opacity: f32,
+            /// Whether the element is drawn at all
+            pub visible: bool,
+            /// Elements with a higher z-index are drawn on top of their siblings, equal ones in the order they were created
+            pub z_index: i32,
+            pub(crate) keyframes: Vec<crate::animation::Keyframe<$name>>,
+            pub(crate) id: usize,
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: $field_default,)*
+                    x: 0.0,
+                    y: 0.0,
+                    anchor_x: 0.0,
+                    anchor_y: 0.0,
+                    angle: 0.0,
+                    scale: 1.0,
+                    scale_x: 1.0,
+                    scale_y: 1.0,
+                    skew_x: 0.0,
+                    skew_y: 0.0,
+                    opacity: 1.0,
+                    visible: true,
+                    z_index: 0,
+                    keyframes: vec![],
+                    id: crate::element::next_element_id(),
+                }
+            }
+        }
+
+        impl $name {
+            /// Add keyframes to the given **Element**, this method is automatically invoked by the `animate!` macro!
+            pub fn add_keyframe<V: crate::animation::Interpolate + Send + Sync + 'static>(
+                &mut self,
+                keyframe_data: crate::animation::KeyframeData<$name, V>,
+            ) {
+                self.keyframes.push(crate::animation::Keyframe::new(keyframe_data));
+            }
+
+            #[doc = concat!("Creates a new **", stringify!($name), "** with its own id, the **keyframes** are copied over")]
+            pub fn duplicate(&self) -> $name {
+                $name {
+                    id: crate::element::next_element_id(),
+                    ..self.clone()
+                }
+            }
+
+            #[doc = concat!("Creates a new **", stringify!($name), "** with its own id, but without any **keyframes**")]
+            pub fn duplicate_without_keyframes(&self) -> $name {
+                $name {
+                    keyframes: vec![],
+                    ..self.duplicate()
+                }
+            }
+        }
+
+        impl crate::element::private::Element for $name {
+            fn box_clone(&self) -> Box<dyn crate::element::Element + Send> {
+                Box::new(self.clone())
+            }
+
+            fn update_data_with_keyframes(&mut self, time_since_start: f32) {
+                // Keyframes are added in chronological order by the `animate!` macro
+                let keyframes = std::mem::take(&mut self.keyframes);
+
+                // Values that are not animated yet hold the start value of their earliest keyframe
+                keyframes
+                    .iter()
+                    .rev()
+                    .filter(|keyframe| !keyframe.has_started(time_since_start))
+                    .for_each(|keyframe| keyframe.update_keyframe_data(self, time_since_start));
+
+                // All other values are determined by their latest keyframe, finished keyframes hold their end value
+                keyframes
+                    .iter()
+                    .filter(|keyframe| keyframe.has_started(time_since_start))
+                    .for_each(|keyframe| keyframe.update_keyframe_data(self, time_since_start));
+
+                self.keyframes = keyframes;
+            }
+
+            fn get_transform(&self) -> crate::element::Transform {
+                crate::element::Transform {
+                    translate: [self.x, self.y],
+                    anchor: [self.anchor_x, self.anchor_y],
+                    angle: self.angle,
+                    scale: [self.scale * self.scale_x, self.scale * self.scale_y],
+                    skew: [self.skew_x, self.skew_y],
+                }
+            }
+
+            fn get_style(&self) -> crate::element::Style {
+                crate::element::Style {
+                    opacity: self.opacity,
+                    visible: self.visible,
+                    z_index: self.z_index,
+                }
+            }
+        }
+
+        impl crate::element::Element for $name {
+            fn get_id(&self) -> usize {
+                self.id
+            }
+        }
+    };
+}
+
+/// Defines an **element** that is drawn by filling and stroking a path, on top of the `element!` macro
+///
+/// Besides the fields of every element, shapes get their fill and stroke style, which are passed on to lyon. The path itself is built
+/// by implementing the `private::Shape` trait by hand.
+macro_rules! shape {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $($fields:tt)*
+        }
+    ) => {
+        element! {
+            $(#[$meta])*
+            pub struct $name {
+                /// Whether the inside of the shape is filled
+                pub fill: bool = true,
+                /// Color of the fill
+                pub fill_color: crate::Color = crate::Color::GREEN,
+                /// Gradient that replaces the fill color
+                pub fill_gradient: Option<crate::Gradient> = None,
+                /// Which regions of overlapping contours are filled, use `EvenOdd` for holes regardless of winding
+                pub fill_rule: crate::FillRule = crate::FillRule::NonZero,
+                /// Whether the outline of the shape is drawn
+                pub stroke: bool = false,
+                /// Color of the outline
+                pub stroke_color: crate::Color = crate::Color::BLACK,
+                /// Width of the outline
+                pub stroke_width: f32 = 0.05,
+                /// How segments of the outline are joined
+                pub line_join: crate::LineJoin = crate::LineJoin::Miter,
+                /// Limit for the length of miter joins relative to the stroke width, longer ones are beveled
+                pub miter_limit: f32 = lyon::tessellation::StrokeOptions::DEFAULT_MITER_LIMIT,
+                /// Cap at the start of the outline and of every dash
+                pub start_cap: crate::LineCap = crate::LineCap::Round,
+                /// Cap at the end of the outline and of every dash
+                pub end_cap: crate::LineCap = crate::LineCap::Round,
+                /// Alternating dash and gap lengths, the outline is solid if this is empty
+                pub dash_array: Vec<f32> = vec![],
+                /// Offset into the dash pattern, animate it for "marching ants"
+                pub dash_offset: f32 = 0.0,
+                /// Fraction of the arc length where the drawn part of the path starts
+                pub trim_start: f32 = 0.0,
+                /// Fraction of the arc length where the drawn part of the path ends, animate it from 0 to 1 to draw the shape in
+                pub trim_end: f32 = 1.0,
+                $($fields)*
+            }
+        }
+
+        impl $name {
+            #[doc = concat!("Returns the outline of the **", stringify!($name), "** as a **Path** in local coordinates, without its style and transform")]
+            pub fn to_path(&self) -> crate::Path {
+                crate::Path {
+                    geometry: crate::element::private::Shape::build_path(
+                        self,
+                        crate::element::shape::TOLERANCE,
+                    ),
+                    ..crate::Path::default()
+                }
+            }
+        }
+
+        impl crate::element::private::Node for $name {
+            fn render(
+                &self,
+                canvas: &mut crate::renderer::Canvas,
+                transform: &lyon::math::Transform,
+                style: &crate::element::Style,
+            ) {
+                let tolerance = crate::element::shape::local_tolerance(canvas, transform);
+                let shape_style = crate::element::shape::ShapeStyle {
+                    fill: self.fill.then(|| self.fill_color),
+                    fill_gradient: self.fill_gradient.as_ref(),
+                    fill_rule: self.fill_rule,
+                    stroke: self.stroke.then(|| crate::element::shape::StrokeStyle {
+                        color: self.stroke_color,
+                        options: lyon::tessellation::StrokeOptions::tolerance(tolerance)
+                        .with_line_width(self.stroke_width.max(0.0))
+                        .with_line_join(self.line_join)
+                        .with_miter_limit(
+                            self.miter_limit
+                                .max(lyon::tessellation::StrokeOptions::MINIMUM_MITER_LIMIT),
+                        )
+                        .with_start_cap(self.start_cap)
+                        .with_end_cap(self.end_cap),
+                        dash_array: &self.dash_array,
+                        dash_offset: self.dash_offset,
+                    }),
+                    trim: (self.trim_start, self.trim_end),
+                };
+
+                crate::element::shape::render(
+                    &crate::element::private::Shape::build_path(self, tolerance),
+                    &shape_style,
+                    tolerance,
+                    canvas,
+                    transform,
+                    style,
+                );
+            }
+        }
+    };
+}
+
+/// Returns an empty **Group** with the transform, opacity and z-index of a shape, to hold the parts it is split into
+macro_rules! parts_group {
+    ($shape:expr) => {
+        crate::Group {
+            x: $shape.x,
+            y: $shape.y,
+            anchor_x: $shape.anchor_x,
+            anchor_y: $shape.anchor_y,
+            angle: $shape.angle,
+            scale: $shape.scale,
+            scale_x: $shape.scale_x,
+            scale_y: $shape.scale_y,
+            skew_x: $shape.skew_x,
+            skew_y: $shape.skew_y,
+            opacity: $shape.opacity,
+            visible: $shape.visible,
+            z_index: $shape.z_index,
+            ..crate::Group::default()
+        }
+    };
+}
+
+/// Returns an empty **Path** with the fill and stroke of a shape, for the parts it is split into
+macro_rules! parts_style {
+    ($shape:expr) => {
+        crate::Path {
+            fill: $shape.fill,
+            fill_color: $shape.fill_color,
+            fill_gradient: $shape.fill_gradient.clone(),
+            fill_rule: $shape.fill_rule,
+            stroke: $shape.stroke,
+            stroke_color: $shape.stroke_color,
+            stroke_width: $shape.stroke_width,
+            line_join: $shape.line_join,
+            miter_limit: $shape.miter_limit,
+            start_cap: $shape.start_cap,
+            end_cap: $shape.end_cap,
+            dash_array: $shape.dash_array.clone(),
+            dash_offset: $shape.dash_offset,
+            trim_start: $shape.trim_start,
+            trim_end: $shape.trim_end,
+            ..crate::Path::default()
+        }
+    };
+}
@@ -0,0 +1,140 @@
+use crate::text::math::{layout, parse, MathBox, Node};
+use crate::text::{Font, MathError, TextAlign, TextGlyphs};
+use lyon::algorithms::aabb::bounding_rect;
+use lyon::geom::Translation;
+use lyon::path::Path;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+shape! {
+    /// A formula typeset from a subset of TeX's math mode, the origin is on its baseline
+    ///
+    /// Supported are fractions (`\frac`), scripts (`^` and `_`), roots (`\sqrt` with an optional index), greek letters,
+    /// common operators, relations and arrows, function names like `\sin`, large operators like `\sum` with limits,
+    /// `\left` and `\right` delimiters, `\text`, spacing commands and the environments `matrix`, `pmatrix`, `bmatrix`,
+    /// `Bmatrix`, `vmatrix`, `Vmatrix` and `cases`. Formulas are set in display style.
+    pub struct Math {
+        /// Source of the formula
+        tex: String = String::new(),
+        /// Parsed formula, kept along with its source so it's not parsed again every frame
+        nodes: Vec<Node> = vec![],
+        /// Layout of the formula in em and the font it was laid out with, so it's not laid out again every frame
+        laid_out: Arc<(Font, MathBox)> = Arc::new((Font::default_math(), MathBox::default())),
+        /// Font the glyphs are taken from, needs math italic letters like the bundled DejaVu Math TeX Gyre
+        pub font: Font = Font::default_math(),
+        /// Height of the em square in scene units
+        pub font_size: f32 = 0.5,
+        /// Alignment of the formula relative to the origin
+        pub align: TextAlign = TextAlign::Left,
+    }
+}
+
+impl Math {
+    /// Parses a formula like `x^2 + \frac{1}{2}`, see `try_tex` for handling invalid formulas
+    ///
+    /// # Panics
+    /// Panics if the formula is not valid or uses anything outside of the supported subset.
+    pub fn tex(tex: &str) -> Self {
+        Math::try_tex(tex).unwrap_or_else(|error| panic!("Invalid formula `{}`: {}", tex, error))
+    }
+
+    /// Parses a formula like `x^2 + \frac{1}{2}`
+    pub fn try_tex(tex: &str) -> Result<Self, MathError> {
+        let mut math = Math {
+            tex: tex.to_string(),
+            nodes: parse(tex)?,
+            ..Math::default()
+        };
+        math.laid_out = Arc::new((math.font.clone(), layout(&math.font.face(), &math.nodes)));
+
+        Ok(math)
+    }
+
+    /// Returns the source of the formula
+    pub fn source(&self) -> &str {
+        &self.tex
+    }
+
+    /// Splits the formula into one **Path** per glyph and bar, so they can be animated on their own
+    ///
+    /// Works like `Text::glyphs`, words are the symbols, fractions, roots and matrices at the top level of the formula.
+    pub fn glyphs(&self) -> TextGlyphs {
+        let face = self.font.face();
+        let math = self.layout(&face);
+        let offset_x = self.offset_x(&math);
+
+        let mut group = parts_group!(self);
+        let mut glyphs = vec![];
+        let mut words = vec![];
+
+        for atom in &math.atoms {
+            let start = glyphs.len();
+
+            for item in &math.items[atom.clone()] {
+                let outline = MathBox::build_item_path(&face, item, self.font_size, offset_x);
+
+                // Glyphs missing in the font have no outline to animate
+                if outline.iter().next().is_none() {
+                    continue;
+                }
+
+                let center = bounding_rect(outline.iter()).center();
+                let glyph_path = crate::Path {
+                    geometry: outline.transformed(&Translation::new(-center.x, -center.y)),
+                    x: center.x,
+                    y: center.y,
+                    ..parts_style!(self)
+                };
+
+                group.add(&glyph_path);
+                glyphs.push(glyph_path);
+            }
+
+            if glyphs.len() > start {
+                words.push(start..glyphs.len());
+            }
+        }
+
+        TextGlyphs::from_words(group, glyphs, words)
+    }
+
+    /// Returns the layout of the formula in em, it's only laid out again if the font was changed since parsing
+    ///
+    /// The layout doesn't depend on the font size, which is applied when building the outlines.
+    fn layout(&self, face: &ttf_parser::Face<'_>) -> Cow<'_, MathBox> {
+        let (font, math) = self.laid_out.as_ref();
+
+        if font.same_as(&self.font) {
+            Cow::Borrowed(math)
+        } else {
+            Cow::Owned(layout(face, &self.nodes))
+        }
+    }
+
+    /// Returns the horizontal offset of the formula in em for its alignment
+    fn offset_x(&self, math: &MathBox) -> f32 {
+        match self.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => -math.width / 2.0,
+            TextAlign::Right => -math.width,
+        }
+    }
+}
+
+impl crate::element::private::Shape for Math {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let face = self.font.face();
+        let math = self.layout(&face);
+        let offset_x = self.offset_x(&math);
+        let font_size = self.font_size.max(0.0);
+
+        let mut builder = Path::builder();
+        for item in &math.items {
+            MathBox::build_item_path(&face, item, font_size, offset_x)
+                .iter()
+                .for_each(|event| builder.path_event(event));
+        }
+
+        builder.build()
+    }
+}
@@ -0,0 +1,413 @@
+use lyon::math::{vector, Point, Vector};
+use lyon::path::iterator::PathIterator;
+use lyon::path::{Path, PathEvent};
+
+/// Maximum number of dashes per subpath, finer patterns can't be told apart from a solid line and would take too long
+const MAX_DASHES: f32 = 10_000.0;
+
+/// A **path** flattened into polylines, so parts of it can be addressed by arc length
+pub(crate) struct MeasuredPath {
+    subpaths: Vec<MeasuredSubpath>,
+}
+
+/// A single polyline of a **MeasuredPath**
+struct MeasuredSubpath {
+    points: Vec<Point>,
+    /// Arc length from the start of the subpath to each point
+    distances: Vec<f32>,
+    closed: bool,
+}
+
+impl MeasuredPath {
+    /// Flattens and measures the given **path**, curves are approximated within the **tolerance**
+    pub fn new(path: &Path, tolerance: f32) -> Self {
+        let mut subpaths = vec![];
+        let mut points = vec![];
+
+        for event in path.iter().flattened(tolerance) {
+            match event {
+                PathEvent::Begin { at } => points = vec![at],
+                PathEvent::Line { to, .. } => points.push(to),
+                PathEvent::End { first, close, .. } => {
+                    // The closing segment is measured like any other segment
+                    if close {
+                        points.push(first);
+                    }
+
+                    subpaths.push(MeasuredSubpath::new(std::mem::take(&mut points), close));
+                }
+                // Curves are already flattened into lines
+                _ => {}
+            }
+        }
+
+        Self { subpaths }
+    }
+
+    /// Total arc length of all subpaths
+    pub fn length(&self) -> f32 {
+        self.subpaths.iter().map(MeasuredSubpath::length).sum()
+    }
+
+    /// Returns the point at the given arc length, subpaths are traversed in order
+    pub fn point_at(&self, distance: f32) -> Point {
+        self.locate(distance)
+            .map(|(subpath, distance)| subpath.point_at(distance))
+            .unwrap_or_default()
+    }
+
+    /// Returns the unit direction of the path at the given arc length, subpaths are traversed in order
+    pub fn tangent_at(&self, distance: f32) -> Vector {
+        self.locate(distance)
+            .map(|(subpath, distance)| subpath.tangent_at(distance))
+            .unwrap_or(vector(1.0, 0.0))
+    }
+
+    /// Returns the polyline between the arc lengths **start** and **end** along the first subpath
+    pub fn polyline(&self, start: f32, end: f32) -> Vec<Point> {
+        self.subpaths
+            .first()
+            .map(|subpath| subpath.polyline(start, end))
+            .unwrap_or_default()
+    }
+
+    /// Returns the subpath the given arc length falls into and the arc length within that subpath
+    fn locate(&self, distance: f32) -> Option<(&MeasuredSubpath, f32)> {
+        let mut offset = 0.0;
+
+        for (index, subpath) in self.subpaths.iter().enumerate() {
+            // Arc lengths beyond the end are clamped by the last subpath
+            if distance <= offset + subpath.length() || index == self.subpaths.len() - 1 {
+                return Some((subpath, distance - offset));
+            }
+
+            offset += subpath.length();
+        }
+
+        None
+    }
+
+    /// Keeps the part of the path between **start** and **end**, given as fractions of the total arc length
+    ///
+    /// Subpaths are traversed in order, a closed subpath that is kept entirely stays closed.
+    pub fn trimmed(&self, start: f32, end: f32) -> Path {
+        let length = self.length();
+        let (start, end) = (start.clamp(0.0, 1.0) * length, end.clamp(0.0, 1.0) * length);
+
+        let mut builder = Path::builder();
+        let mut offset = 0.0;
+
+        for subpath in &self.subpaths {
+            let (subpath_start, subpath_end) = (start - offset, end - offset);
+
+            if subpath.closed && subpath_start <= 0.0 && subpath_end >= subpath.length() {
+                subpath.append_closed(&mut builder);
+            } else {
+                subpath.append_range(&mut builder, subpath_start.max(0.0), subpath_end);
+            }
+
+            offset += subpath.length();
+        }
+
+        builder.build()
+    }
+
+    /// Splits the path into dashes, the **pattern** alternates between dash and gap lengths and is shifted by the **offset**
+    ///
+    /// Like in SVG, a pattern with an odd number of entries is repeated to make it even and every subpath starts the pattern anew.
+    /// Subpaths that would be split into more than `MAX_DASHES` dashes are kept solid.
+    pub fn dashed(&self, pattern: &[f32], offset: f32) -> Path {
+        let pattern: Vec<f32> = match pattern.len() % 2 {
+            0 => pattern.to_vec(),
+            _ => pattern.repeat(2),
+        };
+        let period: f32 = pattern.iter().map(|length| length.max(0.0)).sum();
+
+        let mut builder = Path::builder();
+
+        // Without any dash length there is nothing to split by
+        if period <= 0.0 {
+            for subpath in &self.subpaths {
+                subpath.append_range(&mut builder, 0.0, subpath.length());
+            }
+
+            return builder.build();
+        }
+
+        for subpath in &self.subpaths {
+            let dashes = subpath.length() / period * (pattern.len() / 2) as f32;
+            if dashes > MAX_DASHES {
+                subpath.append_range(&mut builder, 0.0, subpath.length());
+                continue;
+            }
+
+            // Start within the pattern according to the offset, negative offsets shift the other way
+            let mut distance = -offset.rem_euclid(period);
+            let mut index = 0;
+
+            while distance < subpath.length() {
+                let end = distance + pattern[index].max(0.0);
+
+                // Even entries are dashes, odd entries are gaps
+                if index % 2 == 0 && end > 0.0 {
+                    subpath.append_range(&mut builder, distance.max(0.0), end);
+                }
+
+                distance = end;
+                index = (index + 1) % pattern.len();
+            }
+        }
+
+        builder.build()
+    }
+}
+
+impl MeasuredSubpath {
+    fn new(points: Vec<Point>, closed: bool) -> Self {
+        let mut distances = Vec::with_capacity(points.len());
+        let mut distance = 0.0;
+
+        for (index, point) in points.iter().enumerate() {
+            if index > 0 {
+                distance += (*point - points[index - 1]).length();
+            }
+
+            distances.push(distance);
+        }
+
+        Self {
+            points,
+            distances,
+            closed,
+        }
+    }
+
+    fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Returns the point at the given arc length, clamped to the subpath
+    fn point_at(&self, distance: f32) -> Point {
+        if self.points.len() < 2 {
+            return self.points.first().copied().unwrap_or_default();
+        }
+
+        let index = self.segment_at(distance);
+        let (from, to) = (self.distances[index - 1], self.distances[index]);
+        let progress = if to > from {
+            ((distance - from) / (to - from)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        self.points[index - 1].lerp(self.points[index], progress)
+    }
+
+    /// Returns the unit direction of the segment at the given arc length, clamped to the subpath
+    fn tangent_at(&self, distance: f32) -> Vector {
+        if self.points.len() < 2 {
+            return vector(1.0, 0.0);
+        }
+
+        let index = self.segment_at(distance);
+
+        (self.points[index] - self.points[index - 1])
+            .try_normalize()
+            .unwrap_or(vector(1.0, 0.0))
+    }
+
+    /// Returns the index of the end point of the segment the arc length falls onto, needs at least two points
+    fn segment_at(&self, distance: f32) -> usize {
+        self.distances
+            .partition_point(|segment_end| *segment_end < distance)
+            .clamp(1, self.points.len() - 1)
+    }
+
+    /// Returns the points between the arc lengths **start** and **end**, starting and ending exactly there
+    fn polyline(&self, start: f32, end: f32) -> Vec<Point> {
+        if self.points.is_empty() {
+            return vec![];
+        }
+
+        let end = end.clamp(0.0, self.length());
+        let start = start.clamp(0.0, end);
+
+        // All points strictly within the range, between the start and the end point
+        std::iter::once(self.point_at(start))
+            .chain(
+                self.points
+                    .iter()
+                    .zip(&self.distances)
+                    .filter(|(_, distance)| **distance > start && **distance < end)
+                    .map(|(point, _)| *point),
+            )
+            .chain(std::iter::once(self.point_at(end)))
+            .collect()
+    }
+
+    /// Appends the whole subpath as closed polyline to the **builder**
+    fn append_closed(&self, builder: &mut lyon::path::path::Builder) {
+        let Some((first, rest)) = self.points.split_first() else {
+            return;
+        };
+
+        builder.begin(*first);
+
+        // The last point repeats the first one, closing the subpath draws that segment
+        rest[..rest.len().saturating_sub(1)]
+            .iter()
+            .for_each(|point| {
+                builder.line_to(*point);
+            });
+
+        builder.end(true);
+    }
+
+    /// Appends the open polyline between the arc lengths **start** and **end** to the **builder**
+    fn append_range(&self, builder: &mut lyon::path::path::Builder, start: f32, end: f32) {
+        let end = end.min(self.length());
+
+        if self.points.is_empty() || start >= end {
+            return;
+        }
+
+        let points = self.polyline(start, end);
+
+        builder.begin(points[0]);
+        points[1..].iter().for_each(|point| {
+            builder.line_to(*point);
+        });
+        builder.end(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::point;
+
+    /// Builds a path of horizontal lines from x = 0 to each of the **lengths**, one below the other
+    fn lines(lengths: &[f32]) -> MeasuredPath {
+        let mut builder = Path::builder();
+
+        for (index, length) in lengths.iter().enumerate() {
+            builder.begin(point(0.0, index as f32));
+            builder.line_to(point(*length, index as f32));
+            builder.end(false);
+        }
+
+        MeasuredPath::new(&builder.build(), 0.01)
+    }
+
+    /// Returns the x coordinates where each subpath of the **path** starts and ends, and whether it is closed
+    fn spans(path: &Path) -> Vec<(f32, f32, bool)> {
+        let mut spans = vec![];
+        let mut start = 0.0;
+
+        for event in path.iter() {
+            match event {
+                PathEvent::Begin { at } => start = at.x,
+                PathEvent::End { last, close, .. } => spans.push((start, last.x, close)),
+                _ => {}
+            }
+        }
+
+        spans
+    }
+
+    /// Asserts that the **path** has subpaths spanning the **expected** x coordinates
+    fn assert_spans(path: &Path, expected: &[(f32, f32, bool)]) {
+        let actual = spans(path);
+
+        assert_eq!(
+            actual.len(),
+            expected.len(),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual.0 - expected.0).abs() < 1e-4
+                    && (actual.1 - expected.1).abs() < 1e-4
+                    && actual.2 == expected.2,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn odd_dash_patterns_are_repeated() {
+        // Dash 1, gap 2, dash 3, then gap 1, dash 2 and gap 3 with the roles swapped
+        let path = lines(&[12.0]).dashed(&[1.0, 2.0, 3.0], 0.0);
+
+        assert_spans(
+            &path,
+            &[(0.0, 1.0, false), (3.0, 6.0, false), (7.0, 9.0, false)],
+        );
+    }
+
+    #[test]
+    fn dash_offsets_shift_the_pattern_back_and_forth() {
+        let measured = lines(&[4.0]);
+
+        assert_spans(
+            &measured.dashed(&[1.0, 1.0], 0.5),
+            &[(0.0, 0.5, false), (1.5, 2.5, false), (3.5, 4.0, false)],
+        );
+        assert_spans(
+            &measured.dashed(&[1.0, 1.0], -0.5),
+            &[(0.5, 1.5, false), (2.5, 3.5, false)],
+        );
+    }
+
+    #[test]
+    fn dash_patterns_without_length_keep_the_path() {
+        assert_spans(
+            &lines(&[4.0]).dashed(&[0.0, 0.0], 1.0),
+            &[(0.0, 4.0, false)],
+        );
+    }
+
+    #[test]
+    fn patterns_finer_than_the_dash_limit_keep_the_path_solid() {
+        assert_spans(&lines(&[4.0]).dashed(&[1e-6], 0.0), &[(0.0, 4.0, false)]);
+    }
+
+    #[test]
+    fn trimming_with_the_start_after_the_end_keeps_nothing() {
+        assert_spans(&lines(&[4.0]).trimmed(0.75, 0.25), &[]);
+    }
+
+    #[test]
+    fn trimming_continues_across_subpaths() {
+        let path = lines(&[2.0, 2.0]).trimmed(0.25, 0.75);
+
+        assert_spans(&path, &[(1.0, 2.0, false), (0.0, 1.0, false)]);
+    }
+
+    #[test]
+    fn closed_subpaths_kept_whole_stay_closed() {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(1.0, 0.0));
+        builder.line_to(point(1.0, 1.0));
+        builder.line_to(point(0.0, 1.0));
+        builder.close();
+        builder.begin(point(0.0, 2.0));
+        builder.line_to(point(4.0, 2.0));
+        builder.end(false);
+        let measured = MeasuredPath::new(&builder.build(), 0.01);
+
+        // The square ends at its last corner, closing it draws the way back to the start
+        assert_spans(
+            &measured.trimmed(0.0, 0.75),
+            &[(0.0, 0.0, true), (0.0, 2.0, false)],
+        );
+
+        // A square cut open can't be closed anymore
+        assert_spans(&measured.trimmed(0.0, 0.25), &[(0.0, 1.0, false)]);
+    }
+}
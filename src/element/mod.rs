@@ -1,6 +1,53 @@
+#[macro_use]
+mod macros;
+
 mod arrow;
+mod circle;
+mod curve;
+mod decimal_number;
+mod ellipse;
+mod group;
+mod line;
+mod math;
+mod measure;
+mod path;
+mod polygon;
+mod rectangle;
+mod sample;
+mod shape;
+mod star;
+mod style;
+mod text;
+mod text_path;
+mod transform;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub use arrow::{Arrow, ArrowCurve, ArrowTip};
+pub use circle::Circle;
+pub use curve::{ParametricCurve, PolarCurve};
+pub use decimal_number::DecimalNumber;
+pub use ellipse::Ellipse;
+pub use group::Group;
+pub use line::Line;
+pub use math::Math;
+pub use path::{Path, PathBuilder};
+pub use polygon::Polygon;
+pub use rectangle::Rectangle;
+pub(crate) use sample::{polylines_to_path, sample_curve};
+pub use star::Star;
+pub(crate) use style::Style;
+pub use text::Text;
+pub use text_path::TextPath;
+pub(crate) use transform::Transform;
 
-pub use arrow::Arrow;
+/// Global counter for **element** ids, shared by all **Smoothie** instances so ids can never collide
+static NEXT_ELEMENT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocates a new, globally unique **element** id
+pub(crate) fn next_element_id() -> usize {
+    NEXT_ELEMENT_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 pub trait Element: private::Element {
     /// Get **id**
@@ -9,25 +56,45 @@ pub trait Element: private::Element {
 
 // A bit of cheating to implement a partially private trait that is not exposed as API
 pub(crate) mod private {
-    use crate::animation::Keyframe;
-    use crate::renderer::Vertex;
-    use lyon::tessellation::VertexBuffers;
-
-    pub trait Element {
-        /// Tessellates the given **Element** and adds it to the geometry buffer
-        fn render(&self, geometry: &mut VertexBuffers<Vertex, u16>, primitive_id: usize);
+    use crate::element::{Style, Transform};
+    use crate::renderer::Canvas;
+    use lyon::path::Path;
 
+    /// Behaviour shared by all elements, implemented by the `element!` macro
+    pub trait Element: Node {
         /// Clones inside a **Box**
         fn box_clone(&self) -> Box<dyn crate::element::Element + Send>;
 
         /// Update **keyframe** data
         fn update_data_with_keyframes(&mut self, time_since_start: f32);
 
-        /// Get **scale**
-        fn get_scale(&self) -> f32;
+        /// Get the local **transform**, relative to the parent group
+        fn get_transform(&self) -> Transform;
+
+        /// Get the **style**, all per element values besides the transform that are passed to the renderer
+        fn get_style(&self) -> Style;
+    }
+
+    /// Behaviour specific to each element, implemented by hand
+    pub trait Node {
+        /// Tessellates the given **Element** onto the canvas, **transform** and **style** already include those of all parent groups
+        fn render(&self, canvas: &mut Canvas, transform: &lyon::math::Transform, style: &Style);
+
+        /// Get the ids of all direct **children**
+        fn get_children(&self) -> &[usize] {
+            &[]
+        }
 
-        /// Get **angle**
-        fn get_angle(&self) -> f32;
+        /// Takes the **children** that still have to be added to the scene along with this element
+        fn take_pending_children(&mut self) -> Vec<Box<dyn crate::element::Element + Send>> {
+            vec![]
+        }
+    }
+
+    /// Elements drawn by filling and stroking a path, see the `shape!` macro
+    pub trait Shape {
+        /// Builds the **path** of the shape in local coordinates, curves it flattens stay within **tolerance** of the exact ones
+        fn build_path(&self, tolerance: f32) -> Path;
     }
 }
 
@@ -37,3 +104,36 @@ impl Clone for Box<dyn Element + Send> {
         self.box_clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::private::Element as _;
+    use super::Element as _;
+    use crate::{Circle, Easing};
+
+    #[test]
+    fn duplicate_gets_a_fresh_id_and_copies_keyframes() {
+        let mut circle = Circle::default();
+        circle.add_keyframe((
+            |circle, radius| circle.radius = radius,
+            1.0,
+            3.0,
+            0.0,
+            1.0,
+            Easing::Linear,
+        ));
+
+        let mut duplicate = circle.duplicate();
+        assert_ne!(duplicate.get_id(), circle.get_id());
+        assert_eq!(duplicate.keyframes.len(), 1);
+
+        // The copied keyframe animates the duplicate, not the original
+        duplicate.update_data_with_keyframes(0.5);
+        assert_eq!(duplicate.radius, 2.0);
+        assert_eq!(circle.radius, 1.0);
+
+        let without_keyframes = circle.duplicate_without_keyframes();
+        assert_ne!(without_keyframes.get_id(), circle.get_id());
+        assert!(without_keyframes.keyframes.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use lyon::math::{point, vector, Angle};
+use lyon::path::builder::WithSvg;
+use lyon::path::traits::SvgPathBuilder;
+use lyon::path::ArcFlags;
+
+shape! {
+    /// A **Path** of arbitrary geometry, from SVG path data or built command by command
+    ///
+    /// Coordinates are used as they are, SVG's y axis points down while the scene's points up, so set `scale_y` to -1 to keep icons upright.
+    pub struct Path {
+        /// Geometry of the path in local coordinates
+        pub(crate) geometry: lyon::path::Path = lyon::path::Path::new(),
+    }
+}
+
+impl Path {
+    /// Creates a **path** from SVG path data like `M 0 0 L 1 0 C 1 1 0 1 0 0 Z`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path data is invalid, use `try_svg` to handle invalid input
+    pub fn svg(data: &str) -> Self {
+        Path::try_svg(data).unwrap_or_else(|| panic!("Invalid SVG path data: {:?}", data))
+    }
+
+    /// Creates a **path** from SVG path data, returns `None` if the path data is invalid
+    pub fn try_svg(data: &str) -> Option<Self> {
+        let geometry =
+            lyon::svg::path_utils::build_path(lyon::path::Path::svg_builder(), data).ok()?;
+
+        Some(Path {
+            geometry,
+            ..Path::default()
+        })
+    }
+
+    /// Starts building a **path** command by command, like SVG path data
+    pub fn builder() -> PathBuilder {
+        PathBuilder {
+            builder: lyon::path::Path::svg_builder(),
+        }
+    }
+}
+
+impl crate::element::private::Shape for Path {
+    fn build_path(&self, _tolerance: f32) -> lyon::path::Path {
+        self.geometry.clone()
+    }
+}
+
+/// Builds the geometry of a **Path** with the commands of SVG path data, all positions are absolute
+pub struct PathBuilder {
+    builder: WithSvg<lyon::path::path::Builder>,
+}
+
+impl PathBuilder {
+    /// Starts a new subpath at the given position, like `M`
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.builder.move_to(point(x, y));
+        self
+    }
+
+    /// Adds a straight line, like `L`
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.builder.line_to(point(x, y));
+        self
+    }
+
+    /// Adds a quadratic bezier curve with one control point, like `Q`
+    pub fn quadratic_to(mut self, (control_x, control_y): (f32, f32), (x, y): (f32, f32)) -> Self {
+        self.builder
+            .quadratic_bezier_to(point(control_x, control_y), point(x, y));
+        self
+    }
+
+    /// Adds a cubic bezier curve with two control points, like `C`
+    pub fn cubic_to(
+        mut self,
+        (control1_x, control1_y): (f32, f32),
+        (control2_x, control2_y): (f32, f32),
+        (x, y): (f32, f32),
+    ) -> Self {
+        self.builder.cubic_bezier_to(
+            point(control1_x, control1_y),
+            point(control2_x, control2_y),
+            point(x, y),
+        );
+        self
+    }
+
+    /// Adds an elliptical arc, like `A`, the rotation of the ellipse is given in radians
+    pub fn arc_to(
+        mut self,
+        (radius_x, radius_y): (f32, f32),
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        (x, y): (f32, f32),
+    ) -> Self {
+        self.builder.arc_to(
+            vector(radius_x, radius_y),
+            Angle::radians(rotation),
+            ArcFlags { large_arc, sweep },
+            point(x, y),
+        );
+        self
+    }
+
+    /// Closes the current subpath with a straight line to its start, like `Z`
+    pub fn close(mut self) -> Self {
+        self.builder.close();
+        self
+    }
+
+    /// Finishes the geometry and creates the **Path** element
+    pub fn build(self) -> Path {
+        Path {
+            geometry: lyon::path::traits::Build::build(self.builder),
+            ..Path::default()
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use lyon::math::{point, Point};
+use lyon::path::Path;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+shape! {
+    /// A regular **Polygon** around the origin of the element, with one corner pointing up
+    pub struct Polygon {
+        /// Distance from the center to the corners
+        pub radius: f32 = 1.0,
+        /// Number of sides, at least 3
+        pub sides: u32 = 6,
+    }
+}
+
+impl crate::element::private::Shape for Polygon {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let sides = self.sides.max(3);
+        let corners = (0..sides)
+            .map(|index| polar_point(self.radius.max(0.0), index as f32 / sides as f32 * TAU));
+
+        closed_polyline(corners)
+    }
+}
+
+/// Returns the point at the given **radius** and **angle**, angles are measured from the top, counter clockwise
+pub(crate) fn polar_point(radius: f32, angle: f32) -> Point {
+    let angle = angle + FRAC_PI_2;
+    point(radius * angle.cos(), radius * angle.sin())
+}
+
+/// Builds a closed path through all **points**
+pub(crate) fn closed_polyline(mut points: impl Iterator<Item = Point>) -> Path {
+    let mut builder = Path::builder();
+
+    if let Some(first) = points.next() {
+        builder.begin(first);
+        points.for_each(|point| {
+            builder.line_to(point);
+        });
+        builder.close();
+    }
+
+    builder.build()
+}
@@ -0,0 +1,37 @@
+use lyon::math::{point, size, Rect};
+use lyon::path::builder::BorderRadii;
+use lyon::path::traits::PathBuilder;
+use lyon::path::{Path, Winding};
+
+shape! {
+    /// A **Rectangle** centered on the origin of the element, optionally with rounded corners
+    pub struct Rectangle {
+        /// Width of the rectangle
+        pub width: f32 = 2.0,
+        /// Height of the rectangle
+        pub height: f32 = 1.0,
+        /// Radius of the corners, it is reduced to fit if the rectangle is too small
+        pub corner_radius: f32 = 0.0,
+    }
+}
+
+impl crate::element::private::Shape for Rectangle {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let (width, height) = (self.width.max(0.0), self.height.max(0.0));
+        let rect = Rect::new(point(-width / 2.0, -height / 2.0), size(width, height));
+
+        let mut builder = Path::builder();
+
+        if self.corner_radius > 0.0 {
+            builder.add_rounded_rectangle(
+                &rect,
+                &BorderRadii::new(self.corner_radius),
+                Winding::Positive,
+            );
+        } else {
+            builder.add_rectangle(&rect, Winding::Positive);
+        }
+
+        builder.build()
+    }
+}
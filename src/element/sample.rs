@@ -0,0 +1,175 @@
+use lyon::math::Point;
+use lyon::path::Path;
+
+/// Number of equal parts the parameter range is split into before refining, so narrow features between samples aren't missed
+const INITIAL_SEGMENTS: usize = 32;
+
+/// Maximum number of times a part is halved, parts that still deviate then are treated as discontinuities
+const MAX_DEPTH: u32 = 10;
+
+/// Maximum number of points of a sampled curve, parts past it aren't refined any further
+///
+/// Curves that oscillate infinitely often, like `sin(1 / x)` near zero, would otherwise take every part to the maximum depth.
+const MAX_POINTS: usize = 8192;
+
+/// Samples a **curve** over the parameter range from **start** to **end** into polylines
+///
+/// Parts of the range are halved until their middle lies within the **tolerance** of the straight line between their ends,
+/// so flat parts take few points and tight bends many. Points that aren't finite are undefined and break the curve, as do
+/// jumps that don't get smaller when halving further. At most `MAX_POINTS` points are sampled, with some to spare for the
+/// remaining initial parts.
+pub(crate) fn sample_curve(
+    curve: impl Fn(f32) -> Point,
+    start: f32,
+    end: f32,
+    tolerance: f32,
+) -> Vec<Vec<Point>> {
+    // An empty range would only repeat a single point
+    if start == end || !(end - start).is_finite() {
+        return vec![];
+    }
+
+    let mut sampler = Sampler {
+        curve,
+        tolerance: tolerance.max(f32::EPSILON),
+        polylines: vec![vec![]],
+        points: 0,
+    };
+
+    let parameter = |index: usize| start + (end - start) * index as f32 / INITIAL_SEGMENTS as f32;
+    let mut previous = (start, (sampler.curve)(start));
+    sampler.push(previous.1);
+
+    for index in 1..=INITIAL_SEGMENTS {
+        let t = parameter(index);
+        let next = (t, (sampler.curve)(t));
+        sampler.refine(previous, next, 0);
+        previous = next;
+    }
+
+    // Single points don't draw anything
+    sampler.polylines.retain(|polyline| polyline.len() > 1);
+    sampler.polylines
+}
+
+/// Builds a path of open subpaths along the **polylines**, polylines that end where they started are closed
+pub(crate) fn polylines_to_path(polylines: &[Vec<Point>]) -> Path {
+    let mut builder = Path::builder();
+
+    for polyline in polylines.iter().filter(|polyline| polyline.len() > 1) {
+        builder.begin(polyline[0]);
+        for point in &polyline[1..] {
+            builder.line_to(*point);
+        }
+
+        let closed = (polyline[polyline.len() - 1] - polyline[0]).length() <= 1e-5;
+        builder.end(closed);
+    }
+
+    builder.build()
+}
+
+struct Sampler<F> {
+    curve: F,
+    tolerance: f32,
+    polylines: Vec<Vec<Point>>,
+    /// Number of points sampled so far
+    points: usize,
+}
+
+impl<F: Fn(f32) -> Point> Sampler<F> {
+    /// Continues the current polyline with the **point**, or ends it if the point is undefined
+    fn push(&mut self, point: Point) {
+        if is_defined(point) {
+            self.polylines
+                .last_mut()
+                .expect("Starts with a polyline")
+                .push(point);
+        } else {
+            self.split();
+        }
+    }
+
+    /// Ends the current polyline, following points start a new one
+    fn split(&mut self) {
+        if self
+            .polylines
+            .last()
+            .is_some_and(|polyline| !polyline.is_empty())
+        {
+            self.polylines.push(vec![]);
+        }
+    }
+
+    /// Adds the points after **from** up to **to**, the point at **from** is added already
+    fn refine(&mut self, from: (f32, Point), to: (f32, Point), depth: u32) {
+        // Out of points, the initial parts that are left still get their ends
+        if self.points + INITIAL_SEGMENTS >= MAX_POINTS {
+            self.push(to.1);
+            return;
+        }
+
+        self.points += 1;
+        let t = (from.0 + to.0) / 2.0;
+        let middle = (t, (self.curve)(t));
+        let defined = [from.1, middle.1, to.1].map(is_defined);
+
+        if defined == [true; 3] && deviation(from.1, middle.1, to.1) <= self.tolerance {
+            self.push(to.1);
+            return;
+        }
+
+        // Parts that are undefined throughout are skipped, the curve is split at their start already
+        if defined == [false; 3] {
+            return;
+        }
+
+        if depth < MAX_DEPTH {
+            self.refine(from, middle, depth + 1);
+            self.refine(middle, to, depth + 1);
+        } else {
+            // A jump like the pole of tan, or the border of where the curve is defined
+            self.split();
+            self.push(to.1);
+        }
+    }
+}
+
+fn is_defined(point: Point) -> bool {
+    point.x.is_finite() && point.y.is_finite()
+}
+
+/// Distance of the **middle** from the straight line between **from** and **to**
+fn deviation(from: Point, middle: Point, to: Point) -> f32 {
+    let chord = to - from;
+    let length = chord.length();
+
+    // Closed curves return to where they started, the distance to that point is what counts then
+    if length <= f32::EPSILON {
+        return (middle - from).length();
+    }
+
+    let along = ((middle - from).dot(chord) / length).clamp(0.0, length);
+    (middle - (from + chord * (along / length))).length()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::point;
+
+    #[test]
+    fn oscillating_curves_stay_within_the_point_limit() {
+        let polylines = sample_curve(|x| point(x, (1.0 / x).sin()), 1e-4, 1.0, 1e-4);
+        let points: usize = polylines.iter().map(Vec::len).sum();
+
+        assert!(points <= MAX_POINTS + 1);
+        assert_eq!(
+            polylines
+                .last()
+                .and_then(|polyline| polyline.last())
+                .map(|p| p.x),
+            Some(1.0)
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::element::measure::MeasuredPath;
+use crate::element::Style;
+use crate::renderer::{Canvas, Primitive, WithId};
+use crate::{Color, Gradient};
+use lyon::math::Transform;
+use lyon::path::Path;
+use lyon::tessellation::{
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, StrokeOptions, StrokeTessellator,
+};
+
+/// Tolerance for flattening curves in local coordinates where it's not known how large a shape is drawn, like in `to_path`
+pub(crate) const TOLERANCE: f32 = 0.02;
+
+/// Returns the tolerance for flattening curves in the local coordinates of a shape drawn with the **transform**
+///
+/// The tolerance of the **canvas** is divided by the largest factor the transform stretches by, so shapes that are scaled
+/// up by themselves or their groups, and glyphs at small font sizes alike, stay smooth on screen.
+pub(crate) fn local_tolerance(canvas: &Canvas, transform: &Transform) -> f32 {
+    let (a, b, c, d) = (transform.m11, transform.m12, transform.m21, transform.m22);
+
+    // Largest singular value of the linear part of the transform
+    let sum = a * a + b * b + c * c + d * d;
+    let determinant = a * d - b * c;
+    let scale = ((sum
+        + (sum * sum - 4.0 * determinant * determinant)
+            .max(0.0)
+            .sqrt())
+        / 2.0)
+        .sqrt();
+
+    // Shapes scaled to nothing aren't visible, any tolerance does for them
+    if scale.is_finite() && scale > f32::EPSILON {
+        canvas.tolerance / scale
+    } else {
+        canvas.tolerance
+    }
+}
+
+/// How the **path** of a shape is filled and stroked
+pub(crate) struct ShapeStyle<'a> {
+    pub fill: Option<Color>,
+    /// Replaces the fill color, which stays as fallback if the renderer can't take more gradients
+    pub fill_gradient: Option<&'a Gradient>,
+    /// Decides which regions of overlapping contours are inside, `EvenOdd` cuts holes regardless of winding
+    pub fill_rule: FillRule,
+    pub stroke: Option<StrokeStyle<'a>>,
+    /// Fractions of the arc length between which the path is drawn
+    pub trim: (f32, f32),
+}
+
+/// How the outline of a shape is drawn
+pub(crate) struct StrokeStyle<'a> {
+    pub color: Color,
+    pub options: StrokeOptions,
+    /// Alternating dash and gap lengths, the outline is solid if this is empty
+    pub dash_array: &'a [f32],
+    pub dash_offset: f32,
+}
+
+/// Tessellates the **path** of a shape, fill and stroke are drawn with separate primitives so their colors can differ
+///
+/// Curves are flattened to within **tolerance** in local coordinates, see `local_tolerance`.
+pub(crate) fn render(
+    path: &Path,
+    shape_style: &ShapeStyle,
+    tolerance: f32,
+    canvas: &mut Canvas,
+    transform: &Transform,
+    style: &Style,
+) {
+    // Trimming happens first, so the fill and the dashes follow the drawn part of the path
+    let trimmed_path = (shape_style.trim != (0.0, 1.0)).then(|| {
+        let (start, end) = shape_style.trim;
+        MeasuredPath::new(path, tolerance).trimmed(start, end)
+    });
+    let path = trimmed_path.as_ref().unwrap_or(path);
+
+    if let Some(fill_color) = shape_style.fill {
+        let gradient = shape_style
+            .fill_gradient
+            .and_then(|gradient| canvas.add_gradient(gradient));
+
+        let primitive = match gradient {
+            // Only the alpha of the color is used with a gradient, as opacity
+            Some(gradient) => Primitive::new(transform, Color::WHITE.with_opacity(style.opacity))
+                .with_gradient(gradient),
+            None => Primitive::new(transform, fill_color.with_opacity(style.opacity)),
+        };
+
+        let primitive_id = canvas.add_primitive(primitive);
+        let (vertices, indices) = geometry_len(canvas);
+        let result = FillTessellator::new().tessellate_path(
+            path,
+            &FillOptions::tolerance(tolerance).with_fill_rule(shape_style.fill_rule),
+            &mut BuffersBuilder::new(&mut canvas.geometry, WithId(primitive_id)),
+        );
+
+        if let Err(error) = result {
+            log::warn!("Failed to tessellate fill, skipping it: {:?}", error);
+            truncate_geometry(canvas, vertices, indices);
+        }
+    }
+
+    if let Some(stroke) = &shape_style.stroke {
+        let primitive_id = canvas.add_primitive(Primitive::new(
+            transform,
+            stroke.color.with_opacity(style.opacity),
+        ));
+
+        // Dashes are cut out of the path before tessellating the outline
+        let dashed_path = (!stroke.dash_array.is_empty()).then(|| {
+            MeasuredPath::new(path, tolerance).dashed(stroke.dash_array, stroke.dash_offset)
+        });
+
+        let (vertices, indices) = geometry_len(canvas);
+        let result = StrokeTessellator::new().tessellate_path(
+            dashed_path.as_ref().unwrap_or(path),
+            &stroke.options,
+            &mut BuffersBuilder::new(&mut canvas.geometry, WithId(primitive_id)),
+        );
+
+        if let Err(error) = result {
+            log::warn!("Failed to tessellate stroke, skipping it: {:?}", error);
+            truncate_geometry(canvas, vertices, indices);
+        }
+    }
+}
+
+/// Number of vertices and indices in the geometry of the **canvas**
+fn geometry_len(canvas: &Canvas) -> (usize, usize) {
+    (
+        canvas.geometry.vertices.len(),
+        canvas.geometry.indices.len(),
+    )
+}
+
+/// Drops the geometry a failed tessellation left behind, so no partial triangles are drawn
+fn truncate_geometry(canvas: &mut Canvas, vertices: usize, indices: usize) {
+    canvas.geometry.vertices.truncate(vertices);
+    canvas.geometry.indices.truncate(indices);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lyon::math::point;
+
+    /// Adds an axis aligned square contour, counter clockwise unless **clockwise** is set
+    fn add_square(builder: &mut lyon::path::path::Builder, half_size: f32, clockwise: bool) {
+        let mut corners = [
+            point(-half_size, -half_size),
+            point(half_size, -half_size),
+            point(half_size, half_size),
+            point(-half_size, half_size),
+        ];
+
+        if clockwise {
+            corners.reverse();
+        }
+
+        builder.begin(corners[0]);
+        corners[1..].iter().for_each(|corner| {
+            builder.line_to(*corner);
+        });
+        builder.close();
+    }
+
+    /// Fills the **path** with the given rule and returns the area covered by the triangles
+    fn filled_area(path: &Path, fill_rule: FillRule) -> f32 {
+        let mut canvas = Canvas::new(1, 0, TOLERANCE, false);
+        let shape_style = ShapeStyle {
+            fill: Some(Color::BLACK),
+            fill_gradient: None,
+            fill_rule,
+            stroke: None,
+            trim: (0.0, 1.0),
+        };
+
+        render(
+            path,
+            &shape_style,
+            TOLERANCE,
+            &mut canvas,
+            &Transform::identity(),
+            &Style::default(),
+        );
+
+        canvas
+            .geometry
+            .indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2]
+                    .map(|corner| canvas.geometry.vertices[triangle[corner] as usize].position);
+                ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn nested_contours_with_same_winding() {
+        let mut builder = Path::builder();
+        add_square(&mut builder, 1.0, false);
+        add_square(&mut builder, 0.5, false);
+        let path = builder.build();
+
+        // The inner square winds twice and stays filled, even-odd cuts it out
+        assert!((filled_area(&path, FillRule::NonZero) - 4.0).abs() < 1e-4);
+        assert!((filled_area(&path, FillRule::EvenOdd) - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn nested_contours_with_opposite_winding() {
+        let mut builder = Path::builder();
+        add_square(&mut builder, 1.0, false);
+        add_square(&mut builder, 0.5, true);
+        let path = builder.build();
+
+        // The windings cancel out, so both rules leave a hole
+        assert!((filled_area(&path, FillRule::NonZero) - 3.0).abs() < 1e-4);
+        assert!((filled_area(&path, FillRule::EvenOdd) - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn disjoint_contours() {
+        let mut builder = Path::builder();
+        add_square(&mut builder, 1.0, false);
+
+        // A second square beside the first one
+        builder.begin(point(2.0, -0.5));
+        builder.line_to(point(3.0, -0.5));
+        builder.line_to(point(3.0, 0.5));
+        builder.line_to(point(2.0, 0.5));
+        builder.close();
+        let path = builder.build();
+
+        assert!((filled_area(&path, FillRule::NonZero) - 5.0).abs() < 1e-4);
+        assert!((filled_area(&path, FillRule::EvenOdd) - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn tolerance_shrinks_with_the_largest_scale() {
+        let canvas = Canvas::new(1, 0, 0.01, false);
+
+        let tolerance = local_tolerance(
+            &canvas,
+            &Transform::scale(4.0, 0.5).then_rotate(lyon::math::Angle::degrees(30.0)),
+        );
+        assert!((tolerance - 0.0025).abs() < 1e-6);
+
+        // Nothing of shapes scaled to zero is visible
+        assert_eq!(local_tolerance(&canvas, &Transform::scale(0.0, 0.0)), 0.01);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::element::polygon::{closed_polyline, polar_point};
+use lyon::path::Path;
+use std::f32::consts::TAU;
+
+shape! {
+    /// A **Star** around the origin of the element, with one point pointing up
+    pub struct Star {
+        /// Distance from the center to the tips of the points
+        pub outer_radius: f32 = 1.0,
+        /// Distance from the center to the corners between the points
+        pub inner_radius: f32 = 0.4,
+        /// Number of points, at least 2
+        pub points: u32 = 5,
+    }
+}
+
+impl crate::element::private::Shape for Star {
+    fn build_path(&self, _tolerance: f32) -> Path {
+        let points = self.points.max(2);
+
+        // Tips and inner corners alternate, evenly spread around the center
+        let corners = (0..2 * points).map(|index| {
+            let radius = match index % 2 {
+                0 => self.outer_radius,
+                _ => self.inner_radius,
+            };
+
+            polar_point(radius.max(0.0), index as f32 / (2 * points) as f32 * TAU)
+        });
+
+        closed_polyline(corners)
+    }
+}
@@ -0,0 +1,31 @@
+/// Per **element** values, besides the transform, that are passed to the renderer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub opacity: f32,
+    pub visible: bool,
+    /// Elements with a higher z-index are drawn on top, relative to their siblings
+    pub z_index: i32,
+}
+
+impl Style {
+    /// Applies the **style** of a parent group, children are only visible and opaque if their parents are
+    ///
+    /// The opacity is applied to every child on its own, so overlapping children of a translucent group shine through each other.
+    pub fn inherit(&self, parent: &Style) -> Style {
+        Style {
+            opacity: self.opacity * parent.opacity,
+            visible: self.visible && parent.visible,
+            z_index: self.z_index,
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            opacity: 1.0,
+            visible: true,
+            z_index: 0,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::text::{
+    layout, Font, LayoutCache, LayoutKey, LayoutOptions, TextAlign, TextGlyphs, TextLayout,
+};
+use crate::Path;
+use lyon::algorithms::aabb::bounding_rect;
+use lyon::geom::Translation;
+use lyon::math::{point, Point};
+use std::sync::Arc;
+
+shape! {
+    /// A **Text** drawn from the glyph outlines of a font, the origin is on the baseline of the first line
+    ///
+    /// Glyphs are shaped with the kerning and ligatures of the font. Lines break at `\n` and between words if a **max_width** is set.
+    pub struct Text {
+        /// Text to show
+        pub text: String = String::new(),
+        /// Font the glyphs are taken from, the bundled DejaVu Sans by default
+        pub font: Font = Font::default(),
+        /// Height of the em square in scene units
+        pub font_size: f32 = 0.5,
+        /// Distance between the baselines of lines, as multiple of the **font_size**
+        pub line_height: f32 = 1.2,
+        /// Alignment of the lines relative to the origin
+        pub align: TextAlign = TextAlign::Left,
+        /// Width lines are wrapped at, `None` only breaks at `\n`
+        pub max_width: Option<f32> = None,
+        /// Horizontal offset of the first line, to continue after other text
+        pub indent: f32 = 0.0,
+        /// Whether a line is drawn below the text, at the position and thickness given by the font
+        pub underline: bool = false,
+        /// Layout and outline of the last text drawn, so an unchanged text is not shaped and outlined again every frame
+        pub(crate) laid_out: LayoutCache<LaidOutText> = LayoutCache::default(),
+    }
+}
+
+/// The layout of a **Text** together with the outlines of its glyphs, without the underline
+pub(crate) struct LaidOutText {
+    layout: TextLayout,
+    outline: lyon::path::Path,
+}
+
+impl Text {
+    /// Splits the text into one **Path** per glyph, so glyphs can be animated on their own
+    ///
+    /// The group takes the transform, opacity and z-index of the text, the glyphs its fill and stroke. Each glyph is
+    /// positioned at the center of its outline, so it scales and rotates in place. Keyframes of the text are not carried over.
+    pub fn glyphs(&self) -> TextGlyphs {
+        let layout = &self.laid_out_text().layout;
+        let face = self.font.face();
+
+        let mut group = parts_group!(self);
+        let mut glyphs = vec![];
+        let mut clusters = vec![];
+
+        for glyph in &layout.glyphs {
+            let outline = layout.build_glyph_path(&face, glyph);
+
+            // Whitespace has no outline to animate
+            if outline.iter().next().is_none() {
+                continue;
+            }
+
+            let center = bounding_rect(outline.iter()).center();
+            let glyph_path = Path {
+                geometry: outline.transformed(&Translation::new(-center.x, -center.y)),
+                x: glyph.x + center.x,
+                y: glyph.y + center.y,
+                ..parts_style!(self)
+            };
+
+            group.add(&glyph_path);
+            glyphs.push(glyph_path);
+            clusters.push(glyph.cluster);
+        }
+
+        TextGlyphs::new(group, glyphs, &self.text, &clusters)
+    }
+
+    /// Returns the layout and outline of the text, it's only laid out again if the text, font or settings changed since the last one
+    fn laid_out_text(&self) -> Arc<LaidOutText> {
+        let key = LayoutKey {
+            text: self.text.clone(),
+            font: self.font.clone(),
+            options: self.layout_options(),
+        };
+
+        self.laid_out.get_or_build(key, |key| {
+            let layout = layout(&key.font, &key.text, &key.options);
+            let outline = layout.build_path(&key.font);
+
+            LaidOutText { layout, outline }
+        })
+    }
+
+    /// Returns the settings of the layout
+    fn layout_options(&self) -> LayoutOptions {
+        LayoutOptions {
+            font_size: self.font_size.max(0.0),
+            line_height: self.line_height,
+            align: self.align,
+            max_width: self.max_width,
+            indent: self.indent,
+            tabular_digits: false,
+        }
+    }
+
+    /// Returns the position after the last glyph relative to the origin, where following text would continue
+    pub(crate) fn end(&self) -> Point {
+        self.laid_out_text().layout.end
+    }
+}
+
+impl crate::element::private::Shape for Text {
+    fn build_path(&self, _tolerance: f32) -> lyon::path::Path {
+        let laid_out = self.laid_out_text();
+        let (layout, path) = (&laid_out.layout, &laid_out.outline);
+
+        if !self.underline {
+            return path.clone();
+        }
+
+        // Fonts without metrics get an underline a tenth of the size below the baseline
+        let (position, thickness) = self
+            .font
+            .face()
+            .underline_metrics()
+            .map(|metrics| {
+                (
+                    metrics.position as f32 * layout.scale,
+                    metrics.thickness as f32 * layout.scale,
+                )
+            })
+            .unwrap_or((-0.1 * self.font_size, 0.05 * self.font_size));
+
+        let mut builder = lyon::path::Path::builder();
+        path.iter().for_each(|event| builder.path_event(event));
+
+        // Clockwise like the outer contours of TrueType glyphs, so the underline merges with descenders instead of cutting them out
+        for line in layout.lines.iter().filter(|line| line.end > line.start) {
+            let top = line.baseline + position + thickness / 2.0;
+            let bottom = top - thickness;
+
+            builder.begin(point(line.start, top));
+            builder.line_to(point(line.end, top));
+            builder.line_to(point(line.end, bottom));
+            builder.line_to(point(line.start, bottom));
+            builder.close();
+        }
+
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texts_are_only_laid_out_again_when_they_change() {
+        let mut text = Text {
+            text: "Hello world".to_string(),
+            max_width: Some(1.0),
+            ..Text::default()
+        };
+        let first = text.laid_out_text();
+        assert_eq!(first.layout.lines.len(), 2);
+
+        // Moving the text leaves its layout as it is
+        text.x = 2.0;
+        assert!(Arc::ptr_eq(&first, &text.clone().laid_out_text()));
+
+        text.font_size = 0.1;
+        let smaller = text.laid_out_text();
+        assert!(!Arc::ptr_eq(&first, &smaller));
+        assert_eq!(smaller.layout.lines.len(), 1);
+    }
+}
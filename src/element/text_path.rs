@@ -0,0 +1,63 @@
+use crate::element::measure::MeasuredPath;
+use crate::text::{layout, Font, LayoutOptions, TextAlign};
+use lyon::math::{Angle, Transform};
+use lyon::path::Path;
+
+shape! {
+    /// A **TextPath** lays out text along the outline of a **Path**, each glyph is turned to follow the direction of the path
+    ///
+    /// Glyphs stand on the path with their baseline and face to its left, reverse the path to put the text on its other side.
+    /// Glyphs that don't fit on the path are hidden, so text can scroll along it by animating the **start_offset**.
+    pub struct TextPath {
+        /// Text to show, lines after a `\n` follow the path below the first one
+        pub text: String = String::new(),
+        /// Font the glyphs are taken from, the bundled DejaVu Sans by default
+        pub font: Font = Font::default(),
+        /// Height of the em square in scene units
+        pub font_size: f32 = 0.5,
+        /// Path the baseline follows, only its geometry is used, not its transform or style
+        pub path: crate::Path = crate::Path::default(),
+        /// Arc length along the path where the text is aligned
+        pub start_offset: f32 = 0.0,
+        /// Whether the text starts, is centered or ends at the **start_offset**
+        pub align: TextAlign = TextAlign::Left,
+    }
+}
+
+impl crate::element::private::Shape for TextPath {
+    fn build_path(&self, tolerance: f32) -> Path {
+        let options = LayoutOptions {
+            font_size: self.font_size.max(0.0),
+            line_height: 1.2,
+            align: self.align,
+            max_width: None,
+            indent: 0.0,
+            tabular_digits: false,
+        };
+        let layout = layout(&self.font, &self.text, &options);
+        let face = self.font.face();
+        let guide = MeasuredPath::new(&self.path.geometry, tolerance);
+        let length = guide.length();
+
+        let mut builder = Path::builder();
+
+        for glyph in &layout.glyphs {
+            // Glyphs are placed by the middle of their advance, their baseline touches the path there
+            let distance = self.start_offset + glyph.x + glyph.advance / 2.0;
+            if distance < 0.0 || distance > length {
+                continue;
+            }
+
+            let tangent = guide.tangent_at(distance);
+            let placement = Transform::translation(-glyph.advance / 2.0, glyph.y)
+                .then_rotate(Angle::radians(tangent.y.atan2(tangent.x)))
+                .then_translate(guide.point_at(distance).to_vector());
+
+            for event in layout.build_glyph_path(&face, glyph).iter() {
+                builder.path_event(event.transformed(&placement));
+            }
+        }
+
+        builder.build()
+    }
+}
@@ -0,0 +1,37 @@
+use lyon::math::{vector, Angle};
+
+/// The local transform of an **element**, relative to its parent group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translate: [f32; 2],
+    /// Pivot for rotation, scale and skew in local coordinates
+    pub anchor: [f32; 2],
+    pub angle: f32,
+    pub scale: [f32; 2],
+    /// Skew angles along the x and y axis in radians
+    pub skew: [f32; 2],
+}
+
+impl Transform {
+    /// Composes the affine **matrix** of this transform, the anchor stays in place while rotating, scaling and skewing
+    pub fn matrix(&self) -> lyon::math::Transform {
+        let [anchor_x, anchor_y] = self.anchor;
+        let [skew_x, skew_y] = self.skew;
+
+        lyon::math::Transform::translation(-anchor_x, -anchor_y)
+            .then_scale(self.scale[0], self.scale[1])
+            .then(&lyon::math::Transform::new(
+                1.0,
+                skew_y.tan(),
+                skew_x.tan(),
+                1.0,
+                0.0,
+                0.0,
+            ))
+            .then_rotate(Angle::radians(self.angle))
+            .then_translate(vector(
+                anchor_x + self.translate[0],
+                anchor_y + self.translate[1],
+            ))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::animation::Interpolate;
+use crate::Color;
+
+/// Geometry of a **gradient**, positions are given in the space of the gradient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientKind {
+    /// Colors change along the line from **start** to **end**
+    Linear { start: [f32; 2], end: [f32; 2] },
+    /// Colors change from the **center** outwards up to the **radius**
+    Radial { center: [f32; 2], radius: f32 },
+}
+
+/// Coordinate space the geometry of a **gradient** is given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientSpace {
+    /// Relative to the element, the gradient moves and rotates along with it
+    Local,
+    /// Relative to the scene, elements move through the gradient
+    World,
+}
+
+/// A **color** at a position along a **gradient**, the **offset** is between 0 and 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+/// A **gradient** paint with multiple color stops, before the first and after the last stop their colors are kept
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    /// Color stops ordered by their offset, at most `Gradient::MAX_STOPS` are used
+    pub stops: Vec<GradientStop>,
+    pub space: GradientSpace,
+}
+
+impl Gradient {
+    /// Maximum number of color stops the renderer evaluates per gradient
+    pub const MAX_STOPS: usize = 8;
+
+    /// Creates a linear **gradient** in local space from pairs of offsets and colors
+    pub fn linear(start: [f32; 2], end: [f32; 2], stops: &[(f32, Color)]) -> Self {
+        Gradient::new(GradientKind::Linear { start, end }, stops)
+    }
+
+    /// Creates a radial **gradient** in local space from pairs of offsets and colors
+    pub fn radial(center: [f32; 2], radius: f32, stops: &[(f32, Color)]) -> Self {
+        Gradient::new(GradientKind::Radial { center, radius }, stops)
+    }
+
+    /// Returns the **gradient** with its geometry given relative to the scene instead of the element
+    pub fn in_world_space(self) -> Self {
+        Gradient {
+            space: GradientSpace::World,
+            ..self
+        }
+    }
+
+    fn new(kind: GradientKind, stops: &[(f32, Color)]) -> Self {
+        Gradient {
+            kind,
+            stops: stops
+                .iter()
+                .map(|&(offset, color)| GradientStop { offset, color })
+                .collect(),
+            space: GradientSpace::Local,
+        }
+    }
+}
+
+impl Interpolate for GradientStop {
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        GradientStop {
+            offset: self.offset.interpolate(&other.offset, progress),
+            color: self.color.interpolate(&other.color, progress),
+        }
+    }
+}
+
+impl Interpolate for Gradient {
+    // Gradients of the same kind morph into each other, anything else switches once the animation is done
+    fn interpolate(&self, other: &Self, progress: f32) -> Self {
+        let lerp = |from: [f32; 2], to: [f32; 2]| {
+            [0, 1].map(|index| from[index].interpolate(&to[index], progress))
+        };
+
+        let kind = match (self.kind, other.kind) {
+            (
+                GradientKind::Linear { start, end },
+                GradientKind::Linear {
+                    start: other_start,
+                    end: other_end,
+                },
+            ) => GradientKind::Linear {
+                start: lerp(start, other_start),
+                end: lerp(end, other_end),
+            },
+            (
+                GradientKind::Radial { center, radius },
+                GradientKind::Radial {
+                    center: other_center,
+                    radius: other_radius,
+                },
+            ) => GradientKind::Radial {
+                center: lerp(center, other_center),
+                radius: radius.interpolate(&other_radius, progress),
+            },
+            _ if progress < 1.0 => self.kind,
+            _ => other.kind,
+        };
+
+        // The gradient with fewer stops repeats its last stop, so stops can appear and disappear smoothly
+        let stop_count = self.stops.len().max(other.stops.len());
+        let stop_at = |stops: &[GradientStop], index: usize| stops[index.min(stops.len() - 1)];
+
+        let stops = if self.stops.is_empty() || other.stops.is_empty() {
+            if progress < 1.0 {
+                self.stops.clone()
+            } else {
+                other.stops.clone()
+            }
+        } else {
+            (0..stop_count)
+                .map(|index| {
+                    stop_at(&self.stops, index).interpolate(&stop_at(&other.stops, index), progress)
+                })
+                .collect()
+        };
+
+        Gradient {
+            kind,
+            stops,
+            space: if progress < 1.0 {
+                self.space
+            } else {
+                other.space
+            },
+        }
+    }
+}
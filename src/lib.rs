@@ -1,15 +1,48 @@
 extern crate core;
 
 mod animation;
+mod axes;
+mod color;
 mod element;
+mod gradient;
 mod renderer;
 mod smoothie;
+mod svg;
+mod text;
 
 pub use animation::Easing;
+pub use animation::Interpolate;
 pub use animation::Keyframe;
 pub use animation::DOM;
+pub use axes::{Axes, AxesStyle};
+pub use color::Color;
+pub use color::ColorSpace;
 pub use element::Arrow;
+pub use element::ArrowCurve;
+pub use element::ArrowTip;
+pub use element::Circle;
+pub use element::DecimalNumber;
+pub use element::Ellipse;
+pub use element::Group;
+pub use element::Line;
+pub use element::Math;
+pub use element::ParametricCurve;
+pub use element::Path;
+pub use element::PathBuilder;
+pub use element::PolarCurve;
+pub use element::Polygon;
+pub use element::Rectangle;
+pub use element::Star;
+pub use element::Text;
+pub use element::TextPath;
+pub use gradient::{Gradient, GradientKind, GradientSpace, GradientStop};
+pub use lyon::tessellation::{FillRule, LineCap, LineJoin};
 pub use smoothie::Smoothie;
+pub use svg::{Svg, SvgError};
+pub use text::{
+    Code, CodeStyle, Font, FontError, MathError, Reveal, RevealUnit, RichText, TextAlign,
+    TextGlyphs,
+};
 
 /// Returns a **Smothie** instance for rendering a script
 pub fn shake() -> Smoothie {
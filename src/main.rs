@@ -1,4 +1,4 @@
-use smoothie::{animate, Easing};
+use smoothie::{animate, Color, ColorSpace, Easing};
 use std::f32::consts::PI;
 
 fn main() {
@@ -6,12 +6,18 @@ fn main() {
 
     let mut arrow = smoothie.arrow();
     arrow.scale = 0.4;
+    arrow.trim_end = 0.0;
 
     // TODO: create a macro like `arrow!()` that allows to create a arrow with optional arguments
 
     animate! {
         smoothie;
-        arrow,x => 2.5; // x and y are not yet updated in render_state
+        arrow,trim_end => 1.0;
+    };
+
+    animate! {
+        smoothie;
+        arrow,x => 2.5;
         arrow,y => 2.5;
     };
 
@@ -25,6 +31,7 @@ fn main() {
     animate! {
         smoothie;
         arrow,scale => 0.3 + arrow.scale;
+        arrow,fill_color => Color::hex("#1e88e5").interpolated_in(ColorSpace::OkLab);
     }
 
     smoothie.serve();
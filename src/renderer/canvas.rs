@@ -0,0 +1,125 @@
+use crate::color::srgb_to_linear;
+use crate::renderer::gradient_data::GradientData;
+use crate::renderer::primitive::Primitive;
+use crate::renderer::vertex::Vertex;
+use crate::Gradient;
+use lyon::tessellation::VertexBuffers;
+use std::ops::Range;
+
+/// Collects the tessellated geometry of all **elements** in a frame, together with the primitives it is drawn with
+pub struct Canvas {
+    pub(crate) geometry: VertexBuffers<Vertex, u32>,
+    pub(crate) primitives: Vec<Primitive>,
+    pub(crate) gradients: Vec<GradientData>,
+    /// Number of primitives the uniform buffer holds at once, the primitives are drawn in batches of this size
+    batch_len: usize,
+    /// Offsets into the indices at which each batch after the first starts
+    batch_starts: Vec<u32>,
+    /// Maximum number of gradients the uniform buffer can hold
+    gradient_capacity: usize,
+    /// Whether the surface encodes to sRGB itself and therefore expects colors in linear light
+    srgb_surface: bool,
+    /// Tolerance for flattening curves in scene units, shapes scale it to their local coordinates
+    pub(crate) tolerance: f32,
+}
+
+impl Canvas {
+    /// Creates an empty **Canvas** that draws its primitives in batches of **batch_len** and holds at most **gradient_capacity** gradients
+    ///
+    /// Curves are flattened to within **tolerance** in scene units, which should be a fraction of a pixel on screen.
+    pub fn new(
+        batch_len: usize,
+        gradient_capacity: usize,
+        tolerance: f32,
+        srgb_surface: bool,
+    ) -> Self {
+        Self {
+            geometry: VertexBuffers::new(),
+            primitives: Vec::with_capacity(batch_len),
+            gradients: Vec::with_capacity(gradient_capacity),
+            batch_len: batch_len.max(1),
+            batch_starts: vec![],
+            gradient_capacity,
+            srgb_surface,
+            tolerance,
+        }
+    }
+
+    /// Adds a **primitive** and returns its id for tagging vertices, the geometry tagged with it must be added next
+    ///
+    /// Ids count from the start of the batch the primitive is in, as the shader only sees one batch at a time.
+    pub fn add_primitive(&mut self, mut primitive: Primitive) -> u32 {
+        let index = self.primitives.len();
+
+        // Geometry added from here on is drawn with the next batch
+        if index > 0 && index.is_multiple_of(self.batch_len) {
+            self.batch_starts.push(self.geometry.indices.len() as u32);
+        }
+
+        // Colors are given in sRGB, an sRGB surface would otherwise encode them twice
+        if self.srgb_surface {
+            let [r, g, b, a] = primitive.color;
+            primitive.color = [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a];
+        }
+
+        self.primitives.push(primitive);
+
+        (index % self.batch_len) as u32
+    }
+
+    /// Number of batches the primitives are drawn in
+    pub(crate) fn batch_count(&self) -> usize {
+        self.batch_starts.len() + 1
+    }
+
+    /// Ranges of the indices drawn with each batch of primitives
+    pub(crate) fn batches(&self) -> impl Iterator<Item = Range<u32>> + '_ {
+        let ends = self
+            .batch_starts
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.geometry.indices.len() as u32));
+
+        std::iter::once(0)
+            .chain(self.batch_starts.iter().copied())
+            .zip(ends)
+            .map(|(start, end)| start..end)
+    }
+
+    /// Adds a **gradient** and returns its index for a **primitive**, `None` if the gradient buffer is full
+    pub fn add_gradient(&mut self, gradient: &Gradient) -> Option<i32> {
+        if self.gradients.len() >= self.gradient_capacity {
+            log::warn!("Gradient buffer is full, falling back to the flat color");
+            return None;
+        }
+
+        self.gradients
+            .push(GradientData::new(gradient, self.srgb_surface));
+
+        Some(self.gradients.len() as i32 - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use lyon::math::Transform;
+
+    #[test]
+    fn primitives_past_the_batch_length_start_a_new_batch() {
+        let mut canvas = Canvas::new(2, 0, 0.01, false);
+
+        let ids: Vec<u32> = (0..5)
+            .map(|_| {
+                let id = canvas.add_primitive(Primitive::new(&Transform::identity(), Color::BLACK));
+                canvas.geometry.indices.extend([0, 0, 0]);
+                id
+            })
+            .collect();
+
+        assert_eq!(ids, [0, 1, 0, 1, 0]);
+        assert_eq!(canvas.batch_count(), 3);
+        assert_eq!(canvas.batches().collect::<Vec<_>>(), [0..6, 6..12, 12..15]);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::color::srgb_to_linear;
+use crate::{Gradient, GradientKind, GradientSpace};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+// Because the struct implements the `bytemuck::Pod` trait, it may not contain any padding bytes
+/// A **gradient** as the fragment shader evaluates it, referenced by the index stored in a **primitive**
+pub struct GradientData {
+    pub(crate) geometry: [f32; 4], // 16 -> Start and end, or center and radius
+    pub(crate) offsets: [[f32; 4]; 2], // 32 -> Stop offsets, packed for the uniform layout
+    pub(crate) colors: [[f32; 4]; Gradient::MAX_STOPS], // 128 -> Premultiplied stop colors
+    pub(crate) kind: u32,          // 4 -> 0 for linear and 1 for radial gradients
+    pub(crate) stop_count: u32,    // 4
+    pub(crate) world_space: u32,   // 4 -> 1 if the geometry is relative to the scene
+    pub(crate) _pad: u32,          // 4 -> Padding for making sure that we end without padding bytes
+}
+
+impl GradientData {
+    /// Packs a **gradient**, colors are converted to linear light for sRGB surfaces like flat colors
+    pub(crate) fn new(gradient: &Gradient, srgb_surface: bool) -> Self {
+        if gradient.stops.len() > Gradient::MAX_STOPS {
+            log::warn!(
+                "Gradient has {} stops, only the first {} are used",
+                gradient.stops.len(),
+                Gradient::MAX_STOPS
+            );
+        }
+
+        let (kind, geometry) = match gradient.kind {
+            GradientKind::Linear { start, end } => (0, [start[0], start[1], end[0], end[1]]),
+            GradientKind::Radial { center, radius } => (1, [center[0], center[1], radius, 0.0]),
+        };
+
+        let mut data = GradientData {
+            geometry,
+            offsets: [[0.0; 4]; 2],
+            colors: [[0.0; 4]; Gradient::MAX_STOPS],
+            kind,
+            stop_count: 0,
+            world_space: (gradient.space == GradientSpace::World) as u32,
+            _pad: 0,
+        };
+
+        let mut previous_offset = f32::NEG_INFINITY;
+
+        for (index, stop) in gradient.stops.iter().take(Gradient::MAX_STOPS).enumerate() {
+            let [mut r, mut g, mut b, a] = stop.color.to_array();
+
+            if srgb_surface {
+                [r, g, b] = [r, g, b].map(srgb_to_linear);
+            }
+
+            // Like in SVG, a stop can't come before the previous one
+            previous_offset = stop.offset.max(previous_offset);
+
+            // Stops are mixed premultiplied, so transparent stops don't darken their neighbours
+            data.offsets[index / 4][index % 4] = previous_offset;
+            data.colors[index] = [r * a, g * a, b * a, a];
+            data.stop_count += 1;
+        }
+
+        data
+    }
+}
@@ -1,4 +1,6 @@
+mod canvas;
 mod globals;
+mod gradient_data;
 mod primitive;
 mod render_state;
 mod vertex;
@@ -13,7 +15,8 @@ use winit::event_loop::{ControlFlow, EventLoop};
 use winit::platform::run_return::EventLoopExtRunReturn;
 use winit::window::WindowBuilder;
 
-pub use vertex::Vertex;
+pub use canvas::Canvas;
+pub use primitive::Primitive;
 pub use with_id::WithId;
 
 /// The **Renderer** struct
@@ -51,29 +54,27 @@ impl Renderer {
             Event::WindowEvent {
                 ref event,
                 window_id,
-            } if window_id == window.id() => {
-                if !render_state.input(event) {
-                    // Updated event handle!
-                    match event {
-                        WindowEvent::Resized(physical_size) => {
-                            render_state.resize(*physical_size);
-                        }
-                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                            // new_inner_size is &&mut so we have to dereference it twice
-                            render_state.resize(**new_inner_size);
-                        }
-                        WindowEvent::CloseRequested
-                        | WindowEvent::KeyboardInput {
-                            input:
-                                KeyboardInput {
-                                    state: ElementState::Pressed,
-                                    virtual_keycode: Some(VirtualKeyCode::Escape),
-                                    ..
-                                },
-                            ..
-                        } => *control_flow = ControlFlow::Exit,
-                        _ => {}
+            } if window_id == window.id() && !render_state.input(event) => {
+                // Updated event handle!
+                match event {
+                    WindowEvent::Resized(physical_size) => {
+                        render_state.resize(*physical_size);
+                    }
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        // new_inner_size is &&mut so we have to dereference it twice
+                        render_state.resize(**new_inner_size);
                     }
+                    WindowEvent::CloseRequested
+                    | WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::Escape),
+                                ..
+                            },
+                        ..
+                    } => *control_flow = ControlFlow::Exit,
+                    _ => {}
                 }
             }
             Event::RedrawRequested(window_id) if window_id == window.id() => {
@@ -1,27 +1,40 @@
+use crate::Color;
+use lyon::math::Transform;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 // Because the struct implements the `bytemuck::Pod` trait, it may not contain any padding bytes
 /// A **primitive** that is passed to enable uniform rendering for all **Elements**.
 pub struct Primitive {
-    pub(crate) color: [f32; 4],     // 16
-    pub(crate) translate: [f32; 2], // 8!
-    pub(crate) z_index: i32,        // 4
-    pub(crate) angle: f32,          // 4
-    pub(crate) scale: f32,          // 4
-    pub(crate) _pad1: i32, // 4 -> Padding for making sure that we end without padding bytes
-    pub(crate) _pad2: i32, // 4 -> Padding for making sure that we end without padding bytes
-    pub(crate) _pad3: i32, // 4 -> Padding for making sure that we end without padding bytes
+    pub(crate) color: [f32; 4],          // 16
+    pub(crate) transform: [[f32; 2]; 3], // 24 -> Columns of the affine matrix, `mat3x2<f32>` in the shader
+    pub(crate) gradient: i32, // 4 -> Index of the gradient that replaces the color, -1 for flat colors
+    pub(crate) _pad2: i32,    // 4 -> Padding for making sure that we end without padding bytes
 }
 
 impl Primitive {
+    /// Creates the **primitive** for geometry with the given world **transform** and **color**
+    pub(crate) fn new(transform: &Transform, color: Color) -> Self {
+        Primitive {
+            color: color.to_array(),
+            transform: [
+                [transform.m11, transform.m12],
+                [transform.m21, transform.m22],
+                [transform.m31, transform.m32],
+            ],
+            ..Primitive::DEFAULT
+        }
+    }
+
+    /// Returns the **primitive** painted with the gradient at the given index, its color alpha still applies as opacity
+    pub(crate) fn with_gradient(self, gradient: i32) -> Self {
+        Primitive { gradient, ..self }
+    }
+
     pub(crate) const DEFAULT: Self = Primitive {
         color: [0.0; 4],
-        translate: [0.0; 2],
-        z_index: 0,
-        angle: 0.0,
-        scale: 1.0,
-        _pad1: 0,
+        transform: [[1.0, 0.0], [0.0, 1.0], [0.0, 0.0]],
+        gradient: -1,
         _pad2: 0,
-        _pad3: 0,
     };
 }
@@ -1,19 +1,28 @@
 extern crate lyon;
 
 use crate::animation::DOM;
+use crate::element::{Element, Style};
+use crate::renderer::canvas::Canvas;
+use crate::renderer::gradient_data::GradientData;
 use crate::renderer::primitive::Primitive;
 use crate::renderer::vertex::Vertex;
+use lyon::math::Transform;
 
 use crate::renderer::globals::Globals;
-use lyon::tessellation::VertexBuffers;
+use std::collections::{HashMap, HashSet};
 use std::sync::MutexGuard;
 use wgpu::util::DeviceExt;
-use wgpu::{Backends, BindGroup, Buffer};
+use wgpu::{Backends, BindGroup, BindGroupLayout, Buffer};
 use winit::dpi::PhysicalSize;
 use winit::event::WindowEvent;
 use winit::window::Window;
 
+/// Number of primitives drawn per batch, matches the array size in the shader
 const PRIM_BUFFER_LEN: usize = 256;
+const GRADIENT_BUFFER_LEN: usize = 64;
+
+/// Maximum distance in pixels between flattened curves and the exact ones
+const PIXEL_TOLERANCE: f32 = 0.25;
 
 /// The **Renderer** struct
 pub struct RenderState {
@@ -22,9 +31,12 @@ pub struct RenderState {
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
     render_pipeline: wgpu::RenderPipeline,
-    primitives: Vec<Primitive>,
     prims_ubo: Buffer,
+    /// Number of primitive batches the primitive buffer holds
+    prim_batches: usize,
     globals_ubo: Buffer,
+    gradients_ubo: Buffer,
+    bind_group_layout: BindGroupLayout,
     bind_group: BindGroup,
     sample_count: u32,
     size: winit::dpi::PhysicalSize<u32>,
@@ -87,31 +99,19 @@ impl RenderState {
         // Number of samples for anti-aliasing
         let sample_count = 4;
 
-        // Create vector for binding primitives to GPU
-        let mut primitives = Vec::with_capacity(PRIM_BUFFER_LEN);
-        for _ in 0..PRIM_BUFFER_LEN {
-            primitives.push(Primitive {
-                color: [1.0, 0.0, 0.0, 1.0],
-                translate: [0.0; 2],
-                z_index: 0,
-                angle: 0.0,
-                ..Primitive::DEFAULT
-            })
-        }
-
-        // Determine size of primitive buffer
-        let prim_buffer_byte_size = (PRIM_BUFFER_LEN * std::mem::size_of::<Primitive>()) as u64;
+        // Determine size of one batch in the primitive buffer
+        let prim_buffer_byte_size = prim_batch_byte_size();
 
         // Determine size of globals buffer
         let globals_buffer_byte_size = (std::mem::size_of::<Globals>()) as u64;
 
-        // Create primitive buffer
-        let prims_ubo = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Prims ubo"),
-            size: prim_buffer_byte_size,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        // Determine size of gradient buffer
+        let gradient_buffer_byte_size =
+            (GRADIENT_BUFFER_LEN * std::mem::size_of::<GradientData>()) as u64;
+
+        // Create primitive buffer, it grows when a frame needs more batches
+        let prim_batches = 1;
+        let prims_ubo = create_prims_ubo(&device, prim_batches);
 
         // Create globals buffer
         let globals_ubo = device.create_buffer(&wgpu::BufferDescriptor {
@@ -121,6 +121,14 @@ impl RenderState {
             mapped_at_creation: false,
         });
 
+        // Create gradient buffer
+        let gradients_ubo = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Gradients ubo"),
+            size: gradient_buffer_byte_size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // Create bind group layout for uniform buffers
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Bind group layout"),
@@ -128,9 +136,10 @@ impl RenderState {
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
                     visibility: wgpu::ShaderStages::VERTEX,
+                    // Each batch of primitives is bound at its own offset
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
+                        has_dynamic_offset: true,
                         min_binding_size: wgpu::BufferSize::new(prim_buffer_byte_size),
                     },
                     count: None,
@@ -145,24 +154,28 @@ impl RenderState {
                     },
                     count: None,
                 },
+                // Gradients are evaluated per fragment
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(gradient_buffer_byte_size),
+                    },
+                    count: None,
+                },
             ],
         });
 
         // Create bind group
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Bind group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::Buffer(prims_ubo.as_entire_buffer_binding()),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Buffer(globals_ubo.as_entire_buffer_binding()),
-                },
-            ],
-        });
+        let bind_group = create_bind_group(
+            &device,
+            &bind_group_layout,
+            &prims_ubo,
+            &globals_ubo,
+            &gradients_ubo,
+        );
 
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -184,7 +197,7 @@ impl RenderState {
                 entry_point: "fs_main",
                 targets: &[wgpu::ColorTargetState {
                     format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 }],
             }),
@@ -220,9 +233,11 @@ impl RenderState {
             queue,
             config,
             render_pipeline,
-            primitives,
             prims_ubo,
+            prim_batches,
             globals_ubo,
+            gradients_ubo,
+            bind_group_layout,
             bind_group,
             sample_count,
             size,
@@ -244,49 +259,48 @@ impl RenderState {
 
     // TODO: Think about exposing this as a function to the user too, and use the normal function as an **physics** or **update** loop and use this for input only, or expose events and time to smoothie
     /// Processes the given **event**
-    pub fn input(&mut self, event: &WindowEvent) -> bool {
-        match event {
-            WindowEvent::CursorMoved { position, .. } => {
-                self.primitives[0].scale = (position.x as f32 / self.size.width as f32);
-                self.primitives[1].scale = (position.y as f32 / self.size.height as f32);
-
-                return true;
-            }
-            // Not any specific event we're catching, forward it to the main loop
-            _ => return false,
-        }
-
+    pub fn input(&mut self, _event: &WindowEvent) -> bool {
+        // Not any specific event we're catching, forward it to the main loop
         // If this returns true, the event loop is not executed further!
         false
     }
 
     /// Renders the current frame
     pub fn render(&mut self, dom: MutexGuard<DOM>) -> Result<(), wgpu::SurfaceError> {
-        // Create the buffer for tesselation
-        let mut geometry: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        // A pixel spans 2 / (length of the resolution * zoom) scene units, as the shader scales by that length
+        let resolution = (self.size.width as f32).hypot(self.size.height as f32);
+        let tolerance = PIXEL_TOLERANCE * 2.0 / (resolution * self.zoom).max(1.0);
+
+        // Create the buffers for tesselation and primitives
+        let mut canvas = Canvas::new(
+            PRIM_BUFFER_LEN,
+            GRADIENT_BUFFER_LEN,
+            tolerance,
+            self.config.format.describe().srgb,
+        );
 
         // Receive DOM as MutexGuard<DOM> to unlock after rendering
-        dom.iter().for_each(|(element_id, element)| {
-            // Render the element and fill the geometry buffer
-            element.render(&mut geometry, *element_id);
-
-            // Update the primitives according to element data
-            self.primitives[*element_id] = Primitive {
-                color: [0.0, 1.0, 0.0, 1.0],
-                translate: [0.0, 0.0],
-                z_index: 0,
-                scale: element.get_scale(),
-                angle: element.get_angle(),
-                ..Primitive::DEFAULT
-            };
-        });
+        render_dom(&dom, &mut canvas);
+
+        // Grow the primitive buffer if the frame has more batches than it holds
+        if canvas.batch_count() > self.prim_batches {
+            self.prim_batches = canvas.batch_count().next_power_of_two();
+            self.prims_ubo = create_prims_ubo(&self.device, self.prim_batches);
+            self.bind_group = create_bind_group(
+                &self.device,
+                &self.bind_group_layout,
+                &self.prims_ubo,
+                &self.globals_ubo,
+                &self.gradients_ubo,
+            );
+        }
 
         // TODO: Is it necessary to create buffers here every frame?
         let vertex_buffer = self
             .device
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: None,
-                contents: bytemuck::cast_slice(&geometry.vertices),
+                contents: bytemuck::cast_slice(&canvas.geometry.vertices),
                 usage: wgpu::BufferUsages::VERTEX,
             });
 
@@ -294,7 +308,7 @@ impl RenderState {
             .device
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: None,
-                contents: bytemuck::cast_slice(&geometry.indices),
+                contents: bytemuck::cast_slice(&canvas.geometry.indices),
                 usage: wgpu::BufferUsages::INDEX,
             });
 
@@ -354,9 +368,16 @@ impl RenderState {
             }]),
         );
 
-        // Update primitives uniform buffer
+        // Update primitives uniform buffer, the batches follow each other without gaps
         self.queue
-            .write_buffer(&self.prims_ubo, 0, bytemuck::cast_slice(&self.primitives));
+            .write_buffer(&self.prims_ubo, 0, bytemuck::cast_slice(&canvas.primitives));
+
+        // Update gradients uniform buffer
+        self.queue.write_buffer(
+            &self.gradients_ubo,
+            0,
+            bytemuck::cast_slice(&canvas.gradients),
+        );
 
         // command_encoder is borrowed here, but dropped after scope ends to access it later
         {
@@ -364,7 +385,7 @@ impl RenderState {
             // So if sample_count == 1 then we must render directly to the surface's buffer
             let color_attachment = if let Some(msaa_target) = &multisampled_render_target {
                 wgpu::RenderPassColorAttachment {
-                    view: &msaa_target,
+                    view: msaa_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
                         store: true,
@@ -389,10 +410,15 @@ impl RenderState {
             });
 
             render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.bind_group, &[]);
             render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-            render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed((0..geometry.indices.len() as u32), 0, 0..1);
+            render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+
+            // Draw the geometry of each batch with its primitives bound
+            canvas.batches().enumerate().for_each(|(batch, indices)| {
+                let offset = (batch as u64 * prim_batch_byte_size()) as u32;
+                render_pass.set_bind_group(0, &self.bind_group, &[offset]);
+                render_pass.draw_indexed(indices, 0, 0..1);
+            });
         }
 
         // submit will accept anything that implements IntoIter
@@ -407,3 +433,228 @@ impl RenderState {
         self.size
     }
 }
+
+/// Size of one batch of primitives in bytes, a multiple of the 256 byte alignment of uniform buffer offsets
+fn prim_batch_byte_size() -> u64 {
+    (PRIM_BUFFER_LEN * std::mem::size_of::<Primitive>()) as u64
+}
+
+/// Creates a primitive buffer that holds **batches** batches of primitives
+fn create_prims_ubo(device: &wgpu::Device, batches: usize) -> Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Prims ubo"),
+        size: prim_batch_byte_size() * batches as u64,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// Creates the bind group for the uniform buffers, the primitive buffer is bound one batch at a time
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &BindGroupLayout,
+    prims_ubo: &Buffer,
+    globals_ubo: &Buffer,
+    gradients_ubo: &Buffer,
+) -> BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Bind group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: prims_ubo,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(prim_batch_byte_size()),
+                }),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Buffer(globals_ubo.as_entire_buffer_binding()),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::Buffer(gradients_ubo.as_entire_buffer_binding()),
+            },
+        ],
+    })
+}
+
+/// Renders all elements of the **dom** back to front, starting from the roots of the scene graph
+fn render_dom(dom: &DOM, canvas: &mut Canvas) {
+    // Elements that are not part of any group in the DOM are the roots of the scene graph
+    let children: HashSet<usize> = dom
+        .values()
+        .flat_map(|element| element.get_children().iter().copied())
+        .collect();
+
+    let mut roots: Vec<_> = dom
+        .values()
+        .filter(|element| !children.contains(&element.get_id()))
+        .collect();
+
+    // Groups that only contain each other have no root, one of them is drawn as one
+    let mut reachable = HashSet::new();
+    roots
+        .iter()
+        .for_each(|root| collect_reachable(root.get_id(), dom, &mut reachable));
+
+    let parents: HashMap<usize, usize> = dom
+        .values()
+        .flat_map(|element| {
+            element
+                .get_children()
+                .iter()
+                .map(move |child_id| (*child_id, element.get_id()))
+        })
+        .collect();
+
+    let mut ids: Vec<usize> = dom.keys().copied().collect();
+    ids.sort_unstable();
+    ids.into_iter().for_each(|id| {
+        if reachable.contains(&id) {
+            return;
+        }
+
+        // The parents of unreachable elements are unreachable too, so walking up ends on a cycle
+        let mut visited = HashSet::new();
+        let mut root = id;
+        while visited.insert(root) {
+            root = parents[&root];
+        }
+
+        collect_reachable(root, dom, &mut reachable);
+        roots.push(&dom[&root]);
+    });
+
+    // Elements with the same z-index are drawn in the order they were created
+    roots.sort_by_key(|element| element.get_id());
+    let roots = in_drawing_order(roots.into_iter());
+
+    roots.into_iter().for_each(|element| {
+        render_element(
+            element,
+            dom,
+            canvas,
+            &Transform::identity(),
+            &Style::default(),
+            &mut vec![],
+        )
+    });
+}
+
+/// Adds the ids of the element with the given **id** and of all elements within it to **reachable**
+fn collect_reachable(id: usize, dom: &DOM, reachable: &mut HashSet<usize>) {
+    let mut pending = vec![id];
+
+    while let Some(id) = pending.pop() {
+        if let Some(element) = dom.get(&id).filter(|_| reachable.insert(id)) {
+            pending.extend(element.get_children());
+        }
+    }
+}
+
+/// Renders an **element** and all of its children back to front, composing their transforms and styles on the way
+///
+/// The **ancestors** are the ids of the groups the element is rendered within, groups that contain each other are only
+/// rendered once along each branch.
+fn render_element(
+    element: &dyn Element,
+    dom: &DOM,
+    canvas: &mut Canvas,
+    parent_transform: &Transform,
+    parent_style: &Style,
+    ancestors: &mut Vec<usize>,
+) {
+    let transform = element.get_transform().matrix().then(parent_transform);
+    let style = element.get_style().inherit(parent_style);
+
+    // Children of hidden or fully transparent groups can't be seen either
+    if !style.visible || style.opacity <= 0.0 {
+        return;
+    }
+
+    // Render the element, which fills the geometry buffer and adds its primitives
+    element.render(canvas, &transform, &style);
+
+    // Children are drawn on top of their group
+    let children = in_drawing_order(
+        element
+            .get_children()
+            .iter()
+            .filter(|child_id| **child_id != element.get_id() && !ancestors.contains(child_id))
+            .filter_map(|child_id| dom.get(child_id)),
+    );
+
+    ancestors.push(element.get_id());
+    children.into_iter().for_each(|child| {
+        render_element(child, dom, canvas, &transform, &style, ancestors);
+    });
+    ancestors.pop();
+}
+
+/// Sorts **elements** back to front by their z-index, elements with the same z-index keep their order
+fn in_drawing_order<'a>(
+    elements: impl Iterator<Item = &'a Box<dyn Element + Send>>,
+) -> Vec<&'a (dyn Element + Send)> {
+    let mut elements: Vec<&(dyn Element + Send)> =
+        elements.map(|element| element.as_ref()).collect();
+
+    elements.sort_by_key(|element| element.get_style().z_index);
+
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, Group};
+
+    /// Renders the given **elements** like a frame and returns the number of primitives drawn
+    fn rendered_primitives(elements: Vec<Box<dyn Element + Send>>) -> usize {
+        let dom: DOM = elements
+            .into_iter()
+            .map(|element| (element.get_id(), element))
+            .collect();
+
+        let mut canvas = Canvas::new(PRIM_BUFFER_LEN, GRADIENT_BUFFER_LEN, 0.01, false);
+        render_dom(&dom, &mut canvas);
+
+        canvas.primitives.len()
+    }
+
+    #[test]
+    fn elements_are_drawn_within_the_groups_in_the_scene() {
+        let circle = Circle::default();
+        let mut alive = Group::default();
+        let mut removed = Group::default();
+        alive.add(&circle);
+        removed.add(&circle);
+
+        // The removed group isn't part of the frame, so the circle is only drawn within the other one
+        assert_eq!(
+            rendered_primitives(vec![Box::new(alive.clone()), Box::new(circle.clone())]),
+            1
+        );
+        assert_eq!(
+            rendered_primitives(vec![Box::new(alive), Box::new(removed), Box::new(circle)]),
+            2
+        );
+    }
+
+    #[test]
+    fn groups_containing_each_other_are_drawn_once() {
+        let circle = Circle::default();
+        let mut outer = Group::default();
+        let mut inner = Group::default();
+        outer.add(&circle);
+        outer.add(&inner);
+        inner.add(&outer);
+
+        assert_eq!(
+            rendered_primitives(vec![Box::new(outer), Box::new(inner), Box::new(circle)]),
+            1
+        );
+    }
+}
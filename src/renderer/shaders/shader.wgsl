@@ -9,13 +9,11 @@ struct Globals {
 
 struct Primitive {
     color: vec4<f32>;
-    translate: vec2<f32>;
-    z_index: i32;
-    angle: f32;
-    scale: f32;
-    pad1: i32;
+    // Columns of the affine transform matrix composed on the CPU
+    transform: mat3x2<f32>;
+    // Index of the gradient that replaces the color, -1 for flat colors
+    gradient: i32;
     pad2: i32;
-    pad3: i32;
 };
 
 // Primitive struct recieved from CPU in [[stage(vertex)]]
@@ -30,9 +28,31 @@ struct Primitives {
 // Bind group with index 0 and binding with index 1
 [[group(0), binding(1)]] var<uniform> u_globals: Globals;
 
+struct Gradient {
+    // Start and end of linear gradients, center and radius of radial gradients
+    geometry: vec4<f32>;
+    offsets: array<vec4<f32>, 2>;
+    // Premultiplied colors of the stops
+    colors: array<vec4<f32>, 8>;
+    kind: u32;
+    stop_count: u32;
+    world_space: u32;
+    pad: u32;
+};
+
+struct Gradients {
+    gradients: array<Gradient, 64>;
+};
+
+// Bind group with index 0 and binding with index 2
+[[group(0), binding(2)]] var<uniform> u_gradients: Gradients;
+
 struct VertexOutput {
     [[builtin(position)]] clip_position: vec4<f32>;
     [[location(0)]] color: vec4<f32>;
+    [[location(1)]] local_position: vec2<f32>;
+    [[location(2)]] world_position: vec2<f32>;
+    [[location(3), interpolate(flat)]] gradient: i32;
 };
 
 [[stage(vertex)]]
@@ -48,24 +68,65 @@ fn vs_main(
 
     var out: VertexOutput;
 
-    var rotation = mat2x2<f32>(
-       vec2<f32>(cos(prim.angle), -sin(prim.angle)),
-       vec2<f32>(sin(prim.angle), cos(prim.angle))
-    );
+    var scene_pos = prim.transform * vec3<f32>(a_position, 1.0);
 
-    var local_pos = (a_position * prim.scale) * rotation;
-    var world_pos = local_pos - u_globals.offset + prim.translate;
+    var world_pos = scene_pos - u_globals.offset;
 
     var transformed_pos = world_pos * u_globals.zoom / (res / length(res));
 
     out.color = prim.color;
-    out.clip_position = vec4<f32>(transformed_pos, f32(prim.z_index), 1.0);
+    out.local_position = a_position;
+    out.world_position = scene_pos;
+    out.gradient = prim.gradient;
+    // Elements are sorted back to front on the CPU, so no depth is needed
+    out.clip_position = vec4<f32>(transformed_pos, 0.0, 1.0);
     return out;
 }
 
 // Fragment shader
 
+// Evaluates the gradient with the given index at the given position, the result is premultiplied
+fn gradient_color(index: i32, position: vec2<f32>) -> vec4<f32> {
+    // Arrays can only be indexed dynamically in memory, not as values
+    let gradient = &u_gradients.gradients[index];
+    var t: f32;
+
+    if ((*gradient).kind == 0u) {
+        // Project onto the line from start to end
+        let direction = (*gradient).geometry.zw - (*gradient).geometry.xy;
+        t = dot(position - (*gradient).geometry.xy, direction) / max(dot(direction, direction), 0.000001);
+    } else {
+        t = length(position - (*gradient).geometry.xy) / max((*gradient).geometry.z, 0.000001);
+    }
+
+    // Before the first and after the last stop their colors are kept
+    var color = (*gradient).colors[0];
+
+    for (var stop = 1u; stop < (*gradient).stop_count; stop = stop + 1u) {
+        let from = (*gradient).offsets[(stop - 1u) / 4u][(stop - 1u) % 4u];
+        let to = (*gradient).offsets[stop / 4u][stop % 4u];
+
+        if (t > from) {
+            let progress = clamp((t - from) / max(to - from, 0.000001), 0.0, 1.0);
+            color = mix((*gradient).colors[stop - 1u], (*gradient).colors[stop], progress);
+        }
+    }
+
+    return color;
+}
+
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    return in.color;
+    if (in.gradient >= 0) {
+        var position = in.local_position;
+        if (u_gradients.gradients[in.gradient].world_space == 1u) {
+            position = in.world_position;
+        }
+
+        // The alpha of the color carries the opacity of the element
+        return gradient_color(in.gradient, position) * in.color.a;
+    }
+
+    // The pipeline blends with premultiplied alpha
+    return vec4<f32>(in.color.rgb * in.color.a, in.color.a);
 }
\ No newline at end of file
@@ -1,19 +1,22 @@
-use std::any::Any;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::ops::RangeInclusive;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use crate::animation::{Keyframe, Scene, DOM};
+use crate::animation::{Lifespan, Scene, DOM};
 use crate::element::Element;
 use crate::renderer::Renderer;
-use crate::{Arrow, Easing};
+use crate::{
+    Arrow, Circle, DecimalNumber, Ellipse, Group, Line, Math, ParametricCurve, Path, PolarCurve,
+    Polygon, Rectangle, Star, Text, TextPath,
+};
+use crate::{Axes, Code, Color, RichText};
 
 pub struct Smoothie {
     /// List of elements in the scene
     elements: HashMap<usize, Box<dyn Element + Send>>,
-    /// Current element id
-    current_element_id: usize,
+    /// Times at which the elements enter and leave the scene
+    lifespans: HashMap<usize, Lifespan>,
     /// Current time in animation
     current_time: f32,
     /// Scene to update the data in
@@ -25,7 +28,7 @@ impl Smoothie {
     pub(crate) fn new() -> Self {
         Smoothie {
             elements: HashMap::new(),
-            current_element_id: 0,
+            lifespans: HashMap::new(),
             current_time: 0f32,
             scene: Scene::new(),
         }
@@ -33,27 +36,181 @@ impl Smoothie {
 
     /// Creates a new **Arrow**
     pub fn arrow(&mut self) -> Arrow {
-        let arrow = Arrow {
-            x: 0.0,
-            y: 0.0,
-            angle: 0.0,
-            scale: 1.0,
-            stroke: false,
-            fill: true,
-            keyframes: vec![],
-            id: self.current_element_id,
-        };
+        Arrow::default()
+    }
+
+    /// Creates new **Axes** spanning the given data ranges, see `Axes::new`
+    pub fn axes(&mut self, x_range: RangeInclusive<f32>, y_range: RangeInclusive<f32>) -> Axes {
+        Axes::new(x_range, y_range)
+    }
+
+    /// Creates a new **Circle**
+    pub fn circle(&mut self) -> Circle {
+        Circle::default()
+    }
 
-        // Increment element id counter
-        self.current_element_id += 1;
+    /// Creates a new highlighted block of **Code** in the given **language**, see `Code::new`
+    pub fn code(&mut self, source: &str, language: &str) -> Code {
+        Code::new(source, language)
+    }
 
-        arrow
+    /// Creates a new **DecimalNumber** in black with the bundled font, showing the **value**
+    pub fn decimal_number(&mut self, value: f32) -> DecimalNumber {
+        DecimalNumber {
+            value,
+            fill_color: Color::BLACK,
+            ..DecimalNumber::default()
+        }
     }
 
-    /// Adds an **element** to tracking, this method is invoked by the `add!` macro!
-    pub fn add_element(&mut self, element_reference: &(dyn Element)) {
-        self.elements
-            .insert(element_reference.get_id(), element_reference.box_clone());
+    /// Creates a new **Ellipse**
+    pub fn ellipse(&mut self) -> Ellipse {
+        Ellipse::default()
+    }
+
+    /// Creates a new **Rectangle** with sharp corners
+    pub fn rectangle(&mut self) -> Rectangle {
+        Rectangle::default()
+    }
+
+    /// Creates a new **Rectangle** with rounded corners
+    pub fn rounded_rectangle(&mut self) -> Rectangle {
+        Rectangle {
+            corner_radius: 0.2,
+            ..Rectangle::default()
+        }
+    }
+
+    /// Creates a new **Line**, which is stroked instead of filled
+    pub fn line(&mut self) -> Line {
+        Line {
+            fill: false,
+            stroke: true,
+            ..Line::default()
+        }
+    }
+
+    /// Creates a new **Path** from SVG path data, see `Path::svg`
+    pub fn path(&mut self, data: &str) -> Path {
+        Path::svg(data)
+    }
+
+    /// Creates a new **ParametricCurve** over the parameter **range**, which is stroked instead of filled
+    pub fn parametric_curve(
+        &mut self,
+        function: impl Fn(f32) -> (f32, f32) + Send + Sync + 'static,
+        range: RangeInclusive<f32>,
+    ) -> ParametricCurve {
+        let mut curve = ParametricCurve::new(function, range);
+        curve.fill = false;
+        curve.stroke = true;
+        curve
+    }
+
+    /// Creates a new **PolarCurve** over the **range** of angles, which is stroked instead of filled
+    pub fn polar_curve(
+        &mut self,
+        function: impl Fn(f32) -> f32 + Send + Sync + 'static,
+        range: RangeInclusive<f32>,
+    ) -> PolarCurve {
+        let mut curve = PolarCurve::new(function, range);
+        curve.fill = false;
+        curve.stroke = true;
+        curve
+    }
+
+    /// Creates a new regular **Polygon**
+    pub fn polygon(&mut self) -> Polygon {
+        Polygon::default()
+    }
+
+    /// Creates a new **Star**
+    pub fn star(&mut self) -> Star {
+        Star::default()
+    }
+
+    /// Creates a new **Text** in black with the bundled font
+    pub fn text(&mut self, text: &str) -> Text {
+        Text {
+            text: text.to_string(),
+            fill_color: Color::BLACK,
+            ..Text::default()
+        }
+    }
+
+    /// Creates a new **RichText** in black with the bundled font from markup, see `RichText::markup`
+    pub fn rich_text(&mut self, markup: &str) -> RichText {
+        RichText::markup(markup, &self.text(""))
+    }
+
+    /// Creates a new **TextPath** in black with the bundled font, following the outline of the **path**
+    pub fn text_path(&mut self, text: &str, path: &Path) -> TextPath {
+        TextPath {
+            text: text.to_string(),
+            path: path.clone(),
+            fill_color: Color::BLACK,
+            ..TextPath::default()
+        }
+    }
+
+    /// Creates a new formula in black with the bundled math font, see `Math::tex`
+    pub fn math(&mut self, tex: &str) -> Math {
+        let mut math = Math::tex(tex);
+        math.fill_color = Color::BLACK;
+        math
+    }
+
+    /// Creates a new, empty **Group**
+    pub fn group(&mut self) -> Group {
+        Group::default()
+    }
+
+    /// Adds an **element** to the scene at the current animation time
+    pub fn add(&mut self, element_reference: &dyn Element) {
+        let current_time = self.current_time;
+
+        self.lifespans
+            .entry(element_reference.get_id())
+            .and_modify(|lifespan| lifespan.add(current_time))
+            .or_insert_with(|| Lifespan::starting_at(current_time));
+
+        self.add_element(element_reference);
+    }
+
+    /// Removes an **element** from the scene at the current animation time, it may be added again later on
+    pub fn remove(&mut self, element_reference: &dyn Element) {
+        if let Some(lifespan) = self.lifespans.get_mut(&element_reference.get_id()) {
+            lifespan.remove(self.current_time);
+        }
+    }
+
+    /// Adds an **element** to tracking, this method is invoked by the `animate!` macro!
+    ///
+    /// Elements that have not been added explicitly enter the scene with their first animation
+    pub fn add_element(&mut self, element_reference: &dyn Element) {
+        let current_time = self.current_time;
+
+        self.lifespans
+            .entry(element_reference.get_id())
+            .or_insert_with(|| Lifespan::starting_at(current_time));
+
+        self.track(element_reference.box_clone());
+    }
+
+    /// Tracks the snapshot of an **element**, children of groups are tracked as well unless they are known already
+    fn track(&mut self, mut element: Box<dyn Element + Send>) {
+        for child in element.take_pending_children() {
+            if !self.elements.contains_key(&child.get_id()) {
+                // Children live as long as their parents, unless they are added or removed on their own
+                self.lifespans
+                    .entry(child.get_id())
+                    .or_insert_with(|| Lifespan::starting_at(0.0));
+
+                self.track(child);
+            }
+        }
+
+        self.elements.insert(element.get_id(), element);
     }
 
     /// Get the current animation time
@@ -77,9 +234,10 @@ impl Smoothie {
 
         // User DOM
         let elements = self.elements;
+        let lifespans = self.lifespans;
 
         // Create thread to execute user code
-        thread::spawn(move || scene.animate_script(scene_dom, elements));
+        thread::spawn(move || scene.animate_script(scene_dom, elements, lifespans));
 
         // Renderer instance
         let mut renderer = Renderer::new(renderer_dom);
@@ -94,7 +252,7 @@ impl Smoothie {
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
 /// use smoothie::{animate, Easing};
 /// let mut smoothie = smoothie::shake();
 /// let mut arrow = smoothie.arrow();
@@ -113,15 +271,8 @@ macro_rules! animate {
     // Pattern without duration & easing
     ($smoothie:expr; $($object:expr,$property:ident => $value:expr);* $(;)?) => {{
         $(
-            // Generate correct setter function based on element type
-            let setter_fn = match $object {
-                smoothie::Arrow { .. } => |object: &mut smoothie::Arrow, progress: f32| {
-                    object.$property = progress;
-                }
-            };
-
-            // Add keyframes to element
-            $object.add_keyframe((setter_fn, $object.$property, $value, $smoothie.get_current_animation_time(), 1.0, smoothie::Easing::EaseInOut));
+            // Add keyframes to element, the type of the setter is inferred from the element type
+            $object.add_keyframe((|object, progress| object.$property = progress, $object.$property.clone(), $value, $smoothie.get_current_animation_time(), 1.0, smoothie::Easing::EaseInOut));
 
             // Add elements to track list
             $smoothie.add_element(&$object);
@@ -135,15 +286,8 @@ macro_rules! animate {
     // Pattern with duration & without easing
     ($smoothie:expr; duration = $duration:expr; $($object:expr,$property:ident => $value:expr);* $(;)?) => {{
         $(
-            // Generate correct setter function based on element type
-            let setter_fn = match $object {
-                smoothie::Arrow { .. } => |object: &mut smoothie::Arrow, progress: f32| {
-                    object.$property = progress;
-                }
-            };
-
-            // Add keyframes to element
-            $object.add_keyframe((setter_fn, $object.$property, $value, $smoothie.get_current_animation_time(), $duration, smoothie::Easing::EaseInOut));
+            // Add keyframes to element, the type of the setter is inferred from the element type
+            $object.add_keyframe((|object, progress| object.$property = progress, $object.$property.clone(), $value, $smoothie.get_current_animation_time(), $duration, smoothie::Easing::EaseInOut));
 
             // Add elements to track list
             $smoothie.add_element(&$object);
@@ -157,15 +301,8 @@ macro_rules! animate {
     // Pattern with easing & without duration
     ($smoothie:expr; easing = $easing:expr; $($object:expr,$property:ident => $value:expr);* $(;)?) => {{
         $(
-            // Generate correct setter function based on element type
-            let setter_fn = match $object {
-                smoothie::Arrow { .. } => |object: &mut smoothie::Arrow, progress: f32| {
-                    object.$property = progress;
-                }
-            };
-
-            // Add keyframes to element
-            $object.add_keyframe((setter_fn, $object.$property, $value, $smoothie.get_current_animation_time(), 1.0, $easing));
+            // Add keyframes to element, the type of the setter is inferred from the element type
+            $object.add_keyframe((|object, progress| object.$property = progress, $object.$property.clone(), $value, $smoothie.get_current_animation_time(), 1.0, $easing));
 
             // Add elements to track list
             $smoothie.add_element(&$object);
@@ -179,15 +316,8 @@ macro_rules! animate {
     // Pattern with easing & duration
     ($smoothie:expr; easing = $easing:expr; duration = $duration:expr; $($object:expr,$property:ident => $value:expr);* $(;)?) => {{
         $(
-            // Generate correct setter function based on element type
-            let setter_fn = match $object {
-                smoothie::Arrow { .. } => |object: &mut smoothie::Arrow, progress: f32| {
-                    object.$property = progress;
-                }
-            };
-
-            // Add keyframes to element
-            $object.add_keyframe((setter_fn, $object.$property, $value, $smoothie.get_current_animation_time(), $duration, $easing));
+            // Add keyframes to element, the type of the setter is inferred from the element type
+            $object.add_keyframe((|object, progress| object.$property = progress, $object.$property.clone(), $value, $smoothie.get_current_animation_time(), $duration, $easing));
 
             // Add elements to track list
             $smoothie.add_element(&$object);
@@ -201,15 +331,8 @@ macro_rules! animate {
     // Pattern with easing & duration in other direction
     ($smoothie:expr; duration = $duration:expr; easing = $easing:expr; $($object:expr,$property:ident => $value:expr);* $(;)?) => {{
         $(
-            // Generate correct setter function based on element type
-            let setter_fn = match $object {
-                smoothie::Arrow { .. } => |object: &mut smoothie::Arrow, progress: f32| {
-                    object.$property = progress;
-                }
-            };
-
-            // Add keyframes to element
-            $object.add_keyframe((setter_fn, $object.$property, $value, $smoothie.get_current_animation_time(), $duration, $easing));
+            // Add keyframes to element, the type of the setter is inferred from the element type
+            $object.add_keyframe((|object, progress| object.$property = progress, $object.$property.clone(), $value, $smoothie.get_current_animation_time(), $duration, $easing));
 
             // Add elements to track list
             $smoothie.add_element(&$object);
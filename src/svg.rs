@@ -0,0 +1,502 @@
+use crate::{Color, FillRule, Gradient, Group, LineCap, LineJoin, Path};
+use std::collections::HashMap;
+
+/// An SVG document imported as a **Group** of paths
+///
+/// Basic shapes are converted to paths, groups are kept if they have an id. Parts keep the ids they have in the document,
+/// look them up to animate them on their own.
+pub struct Svg {
+    /// Group holding the whole document, centered on its origin and scaled so its larger side spans 2 units
+    pub group: Group,
+    paths: HashMap<String, Path>,
+    groups: HashMap<String, Group>,
+}
+
+/// Errors while importing an SVG document
+#[derive(Debug)]
+pub enum SvgError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The document is not valid SVG
+    Parse(usvg::Error),
+}
+
+impl Svg {
+    /// Imports an SVG document from its source
+    pub fn parse(data: &str) -> Result<Self, SvgError> {
+        // Groups with an id are kept, so they can be animated as a whole
+        let options = usvg::Options {
+            keep_named_groups: true,
+            ..usvg::Options::default()
+        };
+        let tree = usvg::Tree::from_str(data, &options.to_ref()).map_err(SvgError::Parse)?;
+
+        let mut svg = Svg {
+            group: Group::default(),
+            paths: HashMap::new(),
+            groups: HashMap::new(),
+        };
+
+        // The document is centered and flipped, SVG's y axis points down while the scene's points up
+        let view_box = tree.svg_node().view_box.rect;
+        let (center_x, center_y) = (
+            (view_box.x() + view_box.width() / 2.0) as f32,
+            (view_box.y() + view_box.height() / 2.0) as f32,
+        );
+        let scale = 2.0 / view_box.width().max(view_box.height()) as f32;
+
+        let mut document = Group {
+            x: -center_x,
+            y: -center_y,
+            anchor_x: center_x,
+            anchor_y: center_y,
+            scale_x: scale,
+            scale_y: -scale,
+            ..Group::default()
+        };
+
+        for node in tree.root().children() {
+            svg.import_node(&tree, &node, &mut document);
+        }
+
+        svg.group.add(&document);
+
+        Ok(svg)
+    }
+
+    /// Imports an SVG document from a file
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, SvgError> {
+        Svg::parse(&std::fs::read_to_string(path).map_err(SvgError::Io)?)
+    }
+
+    /// Returns the path with the given id in the document
+    pub fn path(&self, id: &str) -> Option<Path> {
+        self.paths.get(id).cloned()
+    }
+
+    /// Returns the group with the given id in the document
+    pub fn group(&self, id: &str) -> Option<Group> {
+        self.groups.get(id).cloned()
+    }
+
+    /// Converts a **node** and its children into elements of the **parent** group
+    fn import_node(&mut self, tree: &usvg::Tree, node: &usvg::Node, parent: &mut Group) {
+        match &*node.borrow() {
+            usvg::NodeKind::Group(group) => {
+                let transform = ElementTransform::new(&group.transform);
+                let mut element = Group {
+                    x: transform.x,
+                    y: transform.y,
+                    angle: transform.angle,
+                    scale_x: transform.scale_x,
+                    scale_y: transform.scale_y,
+                    skew_x: transform.skew_x,
+                    opacity: group.opacity.value() as f32,
+                    ..Group::default()
+                };
+
+                for child in node.children() {
+                    self.import_node(tree, &child, &mut element);
+                }
+
+                // Children have to be added before the group is stored, groups keep snapshots of them
+                if !group.id.is_empty() {
+                    self.groups.insert(group.id.clone(), element.clone());
+                }
+
+                parent.add(&element);
+            }
+            usvg::NodeKind::Path(path) => {
+                let element = import_path(tree, path);
+
+                if !path.id.is_empty() {
+                    self.paths.insert(path.id.clone(), element.clone());
+                }
+
+                parent.add(&element);
+            }
+            usvg::NodeKind::Image(image) => {
+                log::warn!("Skipping image {:?}, images are not supported", image.id);
+            }
+            // Definitions are only used through the paths referencing them
+            _ => {}
+        }
+    }
+}
+
+/// Converts a **path** with its fill and stroke into a **Path** element
+fn import_path(tree: &usvg::Tree, path: &usvg::Path) -> Path {
+    // usvg only produces absolute move, line, cubic and close segments
+    let mut element = path
+        .data
+        .iter()
+        .fold(Path::builder(), |builder, segment| match *segment {
+            usvg::PathSegment::MoveTo { x, y } => builder.move_to(x as f32, y as f32),
+            usvg::PathSegment::LineTo { x, y } => builder.line_to(x as f32, y as f32),
+            usvg::PathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => builder.cubic_to(
+                (x1 as f32, y1 as f32),
+                (x2 as f32, y2 as f32),
+                (x as f32, y as f32),
+            ),
+            usvg::PathSegment::ClosePath => builder.close(),
+        })
+        .build();
+
+    let transform = ElementTransform::new(&path.transform);
+    element.x = transform.x;
+    element.y = transform.y;
+    element.angle = transform.angle;
+    element.scale_x = transform.scale_x;
+    element.scale_y = transform.scale_y;
+    element.skew_x = transform.skew_x;
+    element.visible = path.visibility == usvg::Visibility::Visible;
+
+    let bbox = path.data.bbox();
+
+    element.fill = path.fill.is_some();
+    if let Some(fill) = &path.fill {
+        let (color, gradient) = import_paint(tree, &fill.paint, fill.opacity.value(), bbox);
+
+        element.fill_color = color;
+        element.fill_gradient = gradient;
+        element.fill_rule = match fill.rule {
+            usvg::FillRule::NonZero => FillRule::NonZero,
+            usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+        };
+    }
+
+    element.stroke = path.stroke.is_some();
+    if let Some(stroke) = &path.stroke {
+        // Gradient strokes are not supported, they are drawn in the color of their first stop
+        let (color, _) = import_paint(tree, &stroke.paint, stroke.opacity.value(), bbox);
+        let cap = match stroke.linecap {
+            usvg::LineCap::Butt => LineCap::Butt,
+            usvg::LineCap::Round => LineCap::Round,
+            usvg::LineCap::Square => LineCap::Square,
+        };
+
+        element.stroke_color = color;
+        element.stroke_width = stroke.width.value() as f32;
+        element.start_cap = cap;
+        element.end_cap = cap;
+        element.line_join = match stroke.linejoin {
+            usvg::LineJoin::Miter => LineJoin::Miter,
+            usvg::LineJoin::Round => LineJoin::Round,
+            usvg::LineJoin::Bevel => LineJoin::Bevel,
+        };
+        element.miter_limit = stroke.miterlimit.value() as f32;
+        element.dash_array = stroke
+            .dasharray
+            .iter()
+            .flatten()
+            .map(|length| *length as f32)
+            .collect();
+        element.dash_offset = stroke.dashoffset;
+    }
+
+    element
+}
+
+/// Converts a **paint** into a color and, for gradients, a **Gradient** in the local space of the path
+///
+/// The color of a gradient is the one of its first stop, it is used where gradients are not supported.
+fn import_paint(
+    tree: &usvg::Tree,
+    paint: &usvg::Paint,
+    opacity: f64,
+    bbox: Option<usvg::PathBbox>,
+) -> (Color, Option<Gradient>) {
+    let color = |color: &usvg::Color, opacity: f64| {
+        Color::rgba(
+            color.red as f32 / 255.0,
+            color.green as f32 / 255.0,
+            color.blue as f32 / 255.0,
+            opacity as f32,
+        )
+    };
+
+    let id = match paint {
+        usvg::Paint::Color(paint_color) => return (color(paint_color, opacity), None),
+        usvg::Paint::Link(id) => id,
+    };
+
+    let node = match tree.defs_by_id(id) {
+        Some(node) => node,
+        None => return (Color::BLACK, None),
+    };
+
+    let (base, mut gradient) = match &*node.borrow() {
+        usvg::NodeKind::LinearGradient(linear) => (
+            linear.base.clone(),
+            Gradient::linear(
+                [linear.x1 as f32, linear.y1 as f32],
+                [linear.x2 as f32, linear.y2 as f32],
+                &[],
+            ),
+        ),
+        usvg::NodeKind::RadialGradient(radial) => (
+            radial.base.clone(),
+            Gradient::radial(
+                [radial.cx as f32, radial.cy as f32],
+                radial.r.value() as f32,
+                &[],
+            ),
+        ),
+        _ => {
+            log::warn!("Paint server {:?} is not supported, using black", id);
+            return (Color::BLACK, None);
+        }
+    };
+
+    // The geometry of the gradient is mapped into the space of the path, the focal point of radial gradients is ignored
+    let mut transform = base.transform;
+    if let (usvg::Units::ObjectBoundingBox, Some(bbox)) = (base.units, bbox) {
+        transform.prepend(&usvg::Transform::new(
+            bbox.width(),
+            0.0,
+            0.0,
+            bbox.height(),
+            bbox.x(),
+            bbox.y(),
+        ));
+    }
+
+    let apply = |[x, y]: [f32; 2]| {
+        let (x, y) = transform.apply(x as f64, y as f64);
+        [x as f32, y as f32]
+    };
+
+    gradient.kind = match gradient.kind {
+        crate::GradientKind::Linear { start, end } => crate::GradientKind::Linear {
+            start: apply(start),
+            end: apply(end),
+        },
+        crate::GradientKind::Radial { center, radius } => crate::GradientKind::Radial {
+            center: apply(center),
+            radius: radius
+                * ((transform.a * transform.d - transform.b * transform.c)
+                    .abs()
+                    .sqrt() as f32),
+        },
+    };
+
+    gradient.stops = base
+        .stops
+        .iter()
+        .map(|stop| crate::GradientStop {
+            offset: stop.offset.value() as f32,
+            color: color(&stop.color, stop.opacity.value() * opacity),
+        })
+        .collect();
+
+    let fallback = gradient
+        .stops
+        .first()
+        .map(|stop| stop.color)
+        .unwrap_or(Color::TRANSPARENT);
+
+    (fallback, Some(gradient))
+}
+
+/// The fields of an element that make up an affine **transform**, without skew along the y axis
+struct ElementTransform {
+    x: f32,
+    y: f32,
+    angle: f32,
+    scale_x: f32,
+    scale_y: f32,
+    skew_x: f32,
+}
+
+impl ElementTransform {
+    /// Decomposes the matrix into a scale, followed by a skew along the x axis, a rotation and a translation
+    fn new(transform: &usvg::Transform) -> Self {
+        let usvg::Transform { a, b, c, d, e, f } = *transform;
+
+        // The first column is only rotated and scaled
+        let angle = b.atan2(a);
+        let scale_x = a.hypot(b);
+
+        // Rotating the second column back leaves the skewed and scaled y axis
+        let (sin, cos) = angle.sin_cos();
+        let skewed = c * cos + d * sin;
+        let scale_y = -c * sin + d * cos;
+        let skew_x = if scale_y != 0.0 {
+            (skewed / scale_y).atan()
+        } else {
+            0.0
+        };
+
+        ElementTransform {
+            x: e as f32,
+            y: f as f32,
+            angle: angle as f32,
+            scale_x: scale_x as f32,
+            scale_y: scale_y as f32,
+            skew_x: skew_x as f32,
+        }
+    }
+}
+
+impl std::fmt::Display for SvgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SvgError::Io(error) => write!(f, "Failed to read SVG file: {}", error),
+            SvgError::Parse(error) => write!(f, "Failed to parse SVG document: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::private::Element as _;
+    use crate::element::Element as _;
+
+    /// Asserts that the composed matrix of an element matches the SVG matrix `a b c d e f`
+    fn assert_matrix(matrix: lyon::math::Transform, expected: [f32; 6]) {
+        let actual = [
+            matrix.m11, matrix.m12, matrix.m21, matrix.m22, matrix.m31, matrix.m32,
+        ];
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() < 1e-4,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn nested_groups_keep_their_ids_and_children() {
+        let svg = Svg::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+                <g id="outer">
+                    <g id="inner">
+                        <path id="leaf" d="M 0 0 L 10 0 L 10 10 Z"/>
+                    </g>
+                    <path id="sibling" d="M 20 20 L 30 20 L 30 30 Z"/>
+                </g>
+            </svg>"##,
+        )
+        .unwrap();
+
+        let outer = svg.group("outer").unwrap();
+        let inner = svg.group("inner").unwrap();
+        let leaf = svg.path("leaf").unwrap();
+        let sibling = svg.path("sibling").unwrap();
+
+        assert_eq!(outer.children, vec![inner.get_id(), sibling.get_id()]);
+        assert_eq!(inner.children, vec![leaf.get_id()]);
+
+        // The document group holds everything and sits below the returned group
+        assert_eq!(svg.group.children.len(), 1);
+        assert!(svg.path("missing").is_none());
+    }
+
+    #[test]
+    fn transforms_are_decomposed_into_element_fields() {
+        let svg = Svg::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+                <g id="moved" transform="translate(10 20) rotate(30) scale(2 3)">
+                    <path id="skewed" transform="skewX(20) scale(-1 1)" d="M 0 0 L 10 0 L 10 10 Z"/>
+                </g>
+            </svg>"##,
+        )
+        .unwrap();
+
+        let (sin, cos) = 30f32.to_radians().sin_cos();
+        assert_matrix(
+            svg.group("moved").unwrap().get_transform().matrix(),
+            [2.0 * cos, 2.0 * sin, -3.0 * sin, 3.0 * cos, 10.0, 20.0],
+        );
+
+        let skew = 20f32.to_radians().tan();
+        assert_matrix(
+            svg.path("skewed").unwrap().get_transform().matrix(),
+            [-1.0, 0.0, skew, 1.0, 0.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn basic_shapes_become_styled_paths() {
+        let svg = Svg::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+                <rect id="rect" x="10" y="10" width="30" height="20" rx="5" fill="#ff0000"/>
+                <circle id="circle" cx="50" cy="50" r="10" fill="none" stroke="#0000ff" stroke-width="2"/>
+                <ellipse id="ellipse" cx="50" cy="50" rx="20" ry="10" fill-opacity="0.5"/>
+                <line id="line" x1="0" y1="0" x2="100" y2="100" stroke="black" stroke-linecap="square" stroke-dasharray="4 2"/>
+                <polygon id="polygon" points="0,0 10,0 5,10" fill-rule="evenodd"/>
+            </svg>"##,
+        )
+        .unwrap();
+
+        let rect = svg.path("rect").unwrap();
+        assert!(rect.fill && !rect.stroke);
+        assert_eq!(rect.fill_color, Color::rgb(1.0, 0.0, 0.0));
+        assert!(rect.geometry.iter().count() > 4);
+
+        let circle = svg.path("circle").unwrap();
+        assert!(!circle.fill && circle.stroke);
+        assert_eq!(circle.stroke_color, Color::rgb(0.0, 0.0, 1.0));
+        assert_eq!(circle.stroke_width, 2.0);
+
+        let ellipse = svg.path("ellipse").unwrap();
+        assert_eq!(ellipse.fill_color, Color::rgba(0.0, 0.0, 0.0, 0.5));
+
+        let line = svg.path("line").unwrap();
+        assert!(line.stroke);
+        assert_eq!(line.start_cap, LineCap::Square);
+        assert_eq!(line.dash_array, vec![4.0, 2.0]);
+
+        let polygon = svg.path("polygon").unwrap();
+        assert_eq!(polygon.fill_rule, FillRule::EvenOdd);
+    }
+
+    #[test]
+    fn gradients_are_mapped_into_the_space_of_the_path() {
+        let svg = Svg::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+                <linearGradient id="fade">
+                    <stop offset="0" stop-color="#ffffff"/>
+                    <stop offset="1" stop-color="#000000" stop-opacity="0"/>
+                </linearGradient>
+                <rect id="rect" x="10" y="20" width="40" height="10" fill="url(#fade)"/>
+            </svg>"##,
+        )
+        .unwrap();
+
+        let gradient = svg.path("rect").unwrap().fill_gradient.unwrap();
+
+        // Bounding box units span the rectangle
+        assert_eq!(
+            gradient.kind,
+            crate::GradientKind::Linear {
+                start: [10.0, 20.0],
+                end: [50.0, 20.0]
+            }
+        );
+        assert_eq!(gradient.stops.len(), 2);
+        assert_eq!(gradient.stops[1].color, Color::rgba(0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn invalid_documents_are_rejected() {
+        assert!(matches!(Svg::parse("<svg"), Err(SvgError::Parse(_))));
+        assert!(matches!(
+            Svg::open("does/not/exist.svg"),
+            Err(SvgError::Io(_))
+        ));
+    }
+}
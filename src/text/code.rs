@@ -0,0 +1,520 @@
+use crate::text::{Font, TextAlign};
+use crate::{Color, Easing, Group, Rectangle, Smoothie, Text};
+use std::ops::{Range, RangeInclusive};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Theme used if the theme of a **CodeStyle** is not bundled
+const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Opacity of the lines outside of the highlighted ones
+const DIMMED_OPACITY: f32 = 0.35;
+
+/// Adds a keyframe from the current value of a field to the given value and sets it, like `animate!` without advancing the time
+macro_rules! keyframe {
+    ($element:expr, $field:ident => $value:expr, $start_time:expr, $duration:expr) => {{
+        let value = $value;
+        $element.add_keyframe((
+            |element, value| element.$field = value,
+            $element.$field.clone(),
+            value.clone(),
+            $start_time,
+            $duration,
+            Easing::EaseInOut,
+        ));
+        $element.$field = value;
+    }};
+}
+
+/// How a **Code** block is set
+#[derive(Debug, Clone)]
+pub struct CodeStyle {
+    /// Font of the code and the line numbers, should be monospaced
+    pub font: Font,
+    /// Height of the em square in scene units
+    pub font_size: f32,
+    /// Distance between the baselines of lines, as multiple of the **font_size**
+    pub line_height: f32,
+    /// Name of a bundled theme like `base16-ocean.dark`, `base16-ocean.light`, `InspiredGitHub` or `Solarized (dark)`
+    pub theme: String,
+    /// Whether lines are numbered
+    pub line_numbers: bool,
+}
+
+impl Default for CodeStyle {
+    fn default() -> Self {
+        CodeStyle {
+            font: Font::default_monospace(),
+            font_size: 0.25,
+            line_height: 1.4,
+            theme: DEFAULT_THEME.to_string(),
+            line_numbers: true,
+        }
+    }
+}
+
+/// A block of source **Code**, highlighted by its language and set in a monospaced font on a background
+///
+/// The origin of the group is the top left corner of the background. Every word of the code is a **Text** of its own, so the
+/// code can morph into another version of it, see `transition`. Animations start at the current animation time and advance it.
+pub struct Code {
+    /// Group containing all parts of the block, move it to place the whole block
+    pub group: Group,
+    pub background: Rectangle,
+    /// Rectangle behind the highlighted lines, invisible until lines are highlighted
+    pub highlight: Rectangle,
+    pub line_numbers: Vec<Text>,
+    /// One **Text** per word of the code in reading order, whitespace separates words and highlighting scopes split them
+    pub tokens: Vec<Text>,
+    /// Ranges of tokens on each line
+    lines: Vec<Range<usize>>,
+    /// Distance between the baselines of lines
+    line_advance: f32,
+    /// Space between the background and the code
+    padding: f32,
+}
+
+impl Code {
+    /// Highlights the **source** code, the **language** is a name or file extension like `rust` or `rs`
+    ///
+    /// Unknown languages are shown without highlighting.
+    pub fn new(source: &str, language: &str) -> Self {
+        Code::with_style(source, language, &CodeStyle::default())
+    }
+
+    /// Highlights the **source** code like `new`, set with the given **style**
+    pub fn with_style(source: &str, language: &str, style: &CodeStyle) -> Self {
+        let syntaxes = syntax_set();
+        let syntax = syntaxes
+            .find_syntax_by_token(language)
+            .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+        let theme = theme(&style.theme);
+        let mut highlighter = HighlightLines::new(syntax, theme);
+
+        let font_size = style.font_size.max(0.0);
+        let line_advance = style.line_height * font_size;
+        let padding = font_size;
+
+        // All glyphs of a monospaced font are as wide as a digit
+        let column_width = Text {
+            text: "0".to_string(),
+            font: style.font.clone(),
+            font_size,
+            ..Text::default()
+        }
+        .end()
+        .x;
+
+        let source = source.replace('\t', "    ");
+        let line_count = LinesWithEndings::from(&source).count().max(1);
+        let gutter_columns = if style.line_numbers {
+            line_count.to_string().len() + 2
+        } else {
+            0
+        };
+        let code_start = padding + gutter_columns as f32 * column_width;
+
+        // Baselines sit a bit below the middle of their line, so the glyphs appear centered
+        let baseline =
+            |line: usize| -padding - (line as f32 + 0.5) * line_advance - 0.35 * font_size;
+        let token = |text: &str, color: Color, x: f32, line: usize| Text {
+            text: text.to_string(),
+            font: style.font.clone(),
+            font_size,
+            fill_color: color,
+            x,
+            y: baseline(line),
+            ..Text::default()
+        };
+
+        let mut tokens = vec![];
+        let mut lines = vec![];
+        let mut columns = 0;
+
+        for (line, source_line) in LinesWithEndings::from(&source).enumerate() {
+            let start = tokens.len();
+            let mut column = 0;
+
+            // Lines that can't be highlighted are shown in the foreground color
+            let regions = highlighter
+                .highlight_line(source_line, syntaxes)
+                .unwrap_or_else(|_| vec![(syntect::highlighting::Style::default(), source_line)]);
+
+            for (region_style, region) in regions {
+                let color = to_color(region_style.foreground);
+
+                for (offset, word) in words(region.trim_end_matches(['\n', '\r'])) {
+                    let x = code_start + (column + offset) as f32 * column_width;
+                    tokens.push(token(word, color, x, line));
+                }
+
+                column += region.trim_end_matches(['\n', '\r']).chars().count();
+            }
+
+            columns = columns.max(column);
+            lines.push(start..tokens.len());
+        }
+
+        let width = code_start + columns as f32 * column_width + padding;
+        let height = 2.0 * padding + line_count as f32 * line_advance;
+
+        let background = Rectangle {
+            width,
+            height,
+            x: width / 2.0,
+            y: -height / 2.0,
+            fill_color: theme.settings.background.map_or(Color::BLACK, to_color),
+            z_index: -2,
+            ..Rectangle::default()
+        };
+        let highlight = Rectangle {
+            width,
+            height: line_advance,
+            x: width / 2.0,
+            y: -padding - line_advance / 2.0,
+            fill_color: theme
+                .settings
+                .line_highlight
+                .map_or(Color::WHITE.with_opacity(0.15), to_color),
+            opacity: 0.0,
+            z_index: -1,
+            ..Rectangle::default()
+        };
+
+        let line_number_color = theme
+            .settings
+            .gutter_foreground
+            .map_or(Color::GRAY, to_color);
+        let line_numbers: Vec<Text> = if style.line_numbers {
+            (0..line_count)
+                .map(|line| Text {
+                    align: TextAlign::Right,
+                    ..token(
+                        &(line + 1).to_string(),
+                        line_number_color,
+                        code_start - 2.0 * column_width,
+                        line,
+                    )
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let mut group = Group::default();
+        group.add(&background);
+        group.add(&highlight);
+        line_numbers.iter().for_each(|number| group.add(number));
+        tokens.iter().for_each(|token| group.add(token));
+
+        Code {
+            group,
+            background,
+            highlight,
+            line_numbers,
+            tokens,
+            lines,
+            line_advance,
+            padding,
+        }
+    }
+
+    /// Number of lines of code
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the tokens of the line with the given **number**, counting from 1 like the line numbers
+    pub fn line(&self, number: usize) -> Option<&[Text]> {
+        let range = self.lines.get(number.checked_sub(1)?)?.clone();
+
+        Some(&self.tokens[range])
+    }
+
+    /// Returns the tokens of the line with the given **number** for animating them, counting from 1 like the line numbers
+    pub fn line_mut(&mut self, number: usize) -> Option<&mut [Text]> {
+        let range = self.lines.get(number.checked_sub(1)?)?.clone();
+
+        Some(&mut self.tokens[range])
+    }
+
+    /// Highlights the lines with the given **numbers**, counting from 1, and dims all other lines
+    pub fn highlight_lines(
+        &mut self,
+        smoothie: &mut Smoothie,
+        numbers: RangeInclusive<usize>,
+        duration: f32,
+    ) {
+        let start_time = smoothie.get_current_animation_time();
+        smoothie.add_element(&self.group);
+
+        // The highlight moves from the lines highlighted before, it appears in place if there were none
+        let first = numbers.start().max(&1) - 1;
+        let count = (numbers.end() + 1).saturating_sub(first + 1).max(1);
+        let height = count as f32 * self.line_advance;
+        let y = -self.padding - first as f32 * self.line_advance - height / 2.0;
+
+        if self.highlight.opacity <= 0.0 {
+            self.highlight.y = y;
+            self.highlight.height = height;
+        }
+
+        keyframe!(self.highlight, y => y, start_time, duration);
+        keyframe!(self.highlight, height => height, start_time, duration);
+        keyframe!(self.highlight, opacity => 1.0, start_time, duration);
+        smoothie.add_element(&self.highlight);
+
+        for (line, range) in self.lines.iter().enumerate() {
+            let opacity = if numbers.contains(&(line + 1)) {
+                1.0
+            } else {
+                DIMMED_OPACITY
+            };
+
+            for token in &mut self.tokens[range.clone()] {
+                keyframe!(token, opacity => opacity, start_time, duration);
+                smoothie.add_element(token);
+            }
+        }
+
+        smoothie.increment_animation_time(duration);
+    }
+
+    /// Removes the highlight and shows all lines again
+    pub fn clear_highlight(&mut self, smoothie: &mut Smoothie, duration: f32) {
+        let start_time = smoothie.get_current_animation_time();
+        smoothie.add_element(&self.group);
+
+        keyframe!(self.highlight, opacity => 0.0, start_time, duration);
+        smoothie.add_element(&self.highlight);
+
+        for token in &mut self.tokens {
+            keyframe!(token, opacity => 1.0, start_time, duration);
+            smoothie.add_element(token);
+        }
+
+        smoothie.increment_animation_time(duration);
+    }
+
+    /// Morphs this code into the **target** code, which takes its place in the scene
+    ///
+    /// Tokens found in both versions move to their new place, removed tokens fade out and new ones fade in. Both blocks are
+    /// expected at the same position, once the transition is done this block is removed from the scene.
+    pub fn transition(&mut self, smoothie: &mut Smoothie, target: &mut Code, duration: f32) {
+        let start_time = smoothie.get_current_animation_time();
+        let end_time = start_time + duration;
+        smoothie.add_element(&self.group);
+        smoothie.add_element(&target.group);
+
+        // Matched tokens of the target show up once the tokens of this block arrived at their place
+        let morph = |from: &mut Text, to: &mut Text| {
+            keyframe!(from, x => to.x, start_time, duration);
+            keyframe!(from, y => to.y, start_time, duration);
+            keyframe!(from, fill_color => to.fill_color, start_time, duration);
+            keyframe!(to, opacity => 0.0, start_time, 0.0);
+            keyframe!(to, opacity => 1.0, end_time, 0.0);
+        };
+
+        let matches = match_tokens(&self.tokens, &target.tokens);
+        let mut matched = (
+            vec![false; self.tokens.len()],
+            vec![false; target.tokens.len()],
+        );
+
+        for &(from, to) in &matches {
+            morph(&mut self.tokens[from], &mut target.tokens[to]);
+            matched.0[from] = true;
+            matched.1[to] = true;
+        }
+
+        // Line numbers match by their number, the gutter may widen
+        let numbers = self.line_numbers.len().min(target.line_numbers.len());
+        for (from, to) in self.line_numbers[..numbers]
+            .iter_mut()
+            .zip(&mut target.line_numbers[..numbers])
+        {
+            morph(from, to);
+        }
+
+        let fading_out = self
+            .tokens
+            .iter_mut()
+            .zip(&matched.0)
+            .filter(|(_, matched)| !**matched)
+            .map(|(token, _)| token)
+            .chain(&mut self.line_numbers[numbers..]);
+        for text in fading_out {
+            keyframe!(text, opacity => 0.0, start_time, duration);
+        }
+
+        let fading_in = target
+            .tokens
+            .iter_mut()
+            .zip(&matched.1)
+            .filter(|(_, matched)| !**matched)
+            .map(|(token, _)| token)
+            .chain(&mut target.line_numbers[numbers..]);
+        for text in fading_in {
+            keyframe!(text, opacity => 0.0, start_time, 0.0);
+            keyframe!(text, opacity => 1.0, start_time, duration);
+        }
+
+        // The background resizes into the one of the target, which replaces it at the end
+        keyframe!(self.background, x => target.background.x, start_time, duration);
+        keyframe!(self.background, y => target.background.y, start_time, duration);
+        keyframe!(self.background, width => target.background.width, start_time, duration);
+        keyframe!(self.background, height => target.background.height, start_time, duration);
+        keyframe!(self.background, fill_color => target.background.fill_color, start_time, duration);
+        keyframe!(self.highlight, opacity => 0.0, start_time, duration);
+        keyframe!(target.background, opacity => 0.0, start_time, 0.0);
+        keyframe!(target.background, opacity => 1.0, end_time, 0.0);
+
+        smoothie.add_element(&self.background);
+        smoothie.add_element(&self.highlight);
+        smoothie.add_element(&target.background);
+        self.tokens
+            .iter()
+            .chain(&self.line_numbers)
+            .chain(&target.tokens)
+            .chain(&target.line_numbers)
+            .for_each(|text| smoothie.add_element(text));
+
+        smoothie.increment_animation_time(duration);
+        smoothie.remove(&self.group);
+    }
+}
+
+/// Returns the bundled syntax definitions, loading them once
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Returns the bundled theme with the given **name**, or the default theme if there is none
+fn theme(name: &str) -> &'static Theme {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+
+    let themes = &THEME_SET.get_or_init(ThemeSet::load_defaults).themes;
+    themes.get(name).unwrap_or_else(|| &themes[DEFAULT_THEME])
+}
+
+fn to_color(color: syntect::highlighting::Color) -> Color {
+    Color::rgba(
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+        color.a as f32 / 255.0,
+    )
+}
+
+/// Splits **text** at whitespace into words along with the column they start at
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = vec![];
+    let mut start = None;
+
+    for (column, (index, char)) in text.char_indices().enumerate() {
+        match (start, char.is_whitespace()) {
+            (None, false) => start = Some((column, index)),
+            (Some((start_column, start_index)), true) => {
+                words.push((start_column, &text[start_index..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((start_column, start_index)) = start {
+        words.push((start_column, &text[start_index..]));
+    }
+
+    words
+}
+
+/// Pairs the indices of tokens with equal text in both versions, keeping the longest common subsequence in order
+fn match_tokens(from: &[Text], to: &[Text]) -> Vec<(usize, usize)> {
+    // Length of the longest common subsequence of the tokens after each pair of positions
+    let mut lengths = vec![vec![0usize; to.len() + 1]; from.len() + 1];
+    for i in (0..from.len()).rev() {
+        for j in (0..to.len()).rev() {
+            lengths[i][j] = if from[i].text == to[j].text {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < from.len() && j < to.len() {
+        if from[i].text == to[j].text {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns one token per word of the **source**
+    fn tokens(source: &str) -> Vec<Text> {
+        words(source)
+            .into_iter()
+            .map(|(_, word)| Text {
+                text: word.to_string(),
+                ..Text::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_tokens_all_match() {
+        let source = tokens("let x = 1 ;");
+
+        assert_eq!(
+            match_tokens(&source, &source),
+            vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]
+        );
+    }
+
+    #[test]
+    fn inserted_tokens_are_left_unmatched() {
+        assert_eq!(
+            match_tokens(&tokens("let x = 1 ;"), &tokens("let mut x = 1 ;")),
+            vec![(0, 0), (1, 2), (2, 3), (3, 4), (4, 5)]
+        );
+    }
+
+    #[test]
+    fn deleted_tokens_are_left_unmatched() {
+        assert_eq!(
+            match_tokens(&tokens("let mut x = 1 ;"), &tokens("let x = 2 ;")),
+            vec![(0, 0), (2, 1), (3, 2), (5, 4)]
+        );
+    }
+
+    #[test]
+    fn matches_keep_the_order_of_both_versions() {
+        // Only one of the swapped tokens can keep its place
+        assert_eq!(match_tokens(&tokens("a b"), &tokens("b a")).len(), 1);
+        assert!(match_tokens(&tokens("a b"), &[]).is_empty());
+    }
+
+    #[test]
+    fn words_start_at_their_column() {
+        assert_eq!(words("  fn  main()"), vec![(2, "fn"), (6, "main()")]);
+    }
+}
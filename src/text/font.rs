@@ -0,0 +1,206 @@
+use lyon::math::{point, Transform};
+use std::sync::{Arc, OnceLock};
+
+/// DejaVu Sans, licensed under the Bitstream Vera license, see `assets/fonts/DejaVu-LICENSE.txt`
+static DEFAULT_FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+
+/// DejaVu Sans Bold, under the same license as the default font
+static DEFAULT_BOLD_FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans-Bold.ttf");
+
+/// DejaVu Sans Mono, under the same license as the default font
+static DEFAULT_MONOSPACE_FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/DejaVuSansMono.ttf");
+
+/// DejaVu Math TeX Gyre, under the same license as the default font
+static DEFAULT_MATH_FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/DejaVuMathTeXGyre.ttf");
+
+/// A TrueType or OpenType **font**, cheap to clone since the font data is shared
+#[derive(Clone)]
+pub struct Font {
+    data: Arc<[u8]>,
+    /// Index of the face within a font collection, 0 for single fonts
+    index: u32,
+}
+
+/// Error while loading a **Font**
+#[derive(Debug)]
+pub enum FontError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The data is not a valid TrueType or OpenType font
+    Parse,
+}
+
+impl Font {
+    /// Loads a **font** from a `.ttf` or `.otf` file
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, FontError> {
+        Font::from_bytes(std::fs::read(path).map_err(FontError::Io)?)
+    }
+
+    /// Loads a **font** from the contents of a font file
+    pub fn from_bytes(data: impl Into<Arc<[u8]>>) -> Result<Self, FontError> {
+        Font::from_collection(data, 0)
+    }
+
+    /// Loads the face at the given **index** of a font collection like `.ttc` files
+    pub fn from_collection(data: impl Into<Arc<[u8]>>, index: u32) -> Result<Self, FontError> {
+        let font = Font {
+            data: data.into(),
+            index,
+        };
+
+        // Fonts are parsed again when used, so only valid fonts are accepted here
+        match rustybuzz::Face::from_slice(&font.data, index) {
+            Some(_) => Ok(font),
+            None => Err(FontError::Parse),
+        }
+    }
+
+    /// Returns the bold variant of the bundled DejaVu Sans
+    pub fn default_bold() -> Self {
+        static DEFAULT_BOLD_FONT: OnceLock<Font> = OnceLock::new();
+
+        DEFAULT_BOLD_FONT
+            .get_or_init(|| {
+                Font::from_bytes(DEFAULT_BOLD_FONT_DATA).expect("Bundled font is valid")
+            })
+            .clone()
+    }
+
+    /// Returns the bundled DejaVu Sans Mono, where all glyphs have the same width
+    pub fn default_monospace() -> Self {
+        static DEFAULT_MONOSPACE_FONT: OnceLock<Font> = OnceLock::new();
+
+        DEFAULT_MONOSPACE_FONT
+            .get_or_init(|| {
+                Font::from_bytes(DEFAULT_MONOSPACE_FONT_DATA).expect("Bundled font is valid")
+            })
+            .clone()
+    }
+
+    /// Returns the bundled DejaVu Math TeX Gyre, which covers math italic letters and symbols
+    pub fn default_math() -> Self {
+        static DEFAULT_MATH_FONT: OnceLock<Font> = OnceLock::new();
+
+        DEFAULT_MATH_FONT
+            .get_or_init(|| {
+                Font::from_bytes(DEFAULT_MATH_FONT_DATA).expect("Bundled font is valid")
+            })
+            .clone()
+    }
+
+    /// Returns the parsed face for shaping and reading glyph outlines
+    pub(crate) fn face(&self) -> rustybuzz::Face<'_> {
+        rustybuzz::Face::from_slice(&self.data, self.index)
+            .expect("Font data is validated when loading")
+    }
+
+    /// Returns whether both fonts are the same face of the same loaded data
+    pub(crate) fn same_as(&self, other: &Font) -> bool {
+        Arc::ptr_eq(&self.data, &other.data) && self.index == other.index
+    }
+}
+
+impl Default for Font {
+    /// Returns the bundled DejaVu Sans
+    fn default() -> Self {
+        static DEFAULT_FONT: OnceLock<Font> = OnceLock::new();
+
+        DEFAULT_FONT
+            .get_or_init(|| Font::from_bytes(DEFAULT_FONT_DATA).expect("Bundled font is valid"))
+            .clone()
+    }
+}
+
+impl PartialEq for Font {
+    // Fonts are equal if they share their data, comparing megabytes of font data would be wasteful
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data) && self.index == other.index
+    }
+}
+
+impl std::fmt::Debug for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Font")
+            .field("bytes", &self.data.len())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "Failed to read font file: {}", error),
+            FontError::Parse => write!(f, "Failed to parse font, expected TrueType or OpenType"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// Appends the outline of a glyph to a lyon path **builder**, the **transform** maps from font units
+pub(crate) fn append_glyph_outline(
+    face: &ttf_parser::Face<'_>,
+    glyph_id: u16,
+    transform: &Transform,
+    builder: &mut lyon::path::path::Builder,
+) {
+    let mut sink = OutlineSink {
+        builder,
+        transform,
+        open: false,
+    };
+
+    face.outline_glyph(ttf_parser::GlyphId(glyph_id), &mut sink);
+
+    // Contours of valid fonts are closed, but a broken one must not leave the builder in a subpath
+    if sink.open {
+        sink.builder.end(false);
+    }
+}
+
+/// Forwards glyph outlines from **ttf_parser** to a lyon path builder
+struct OutlineSink<'a> {
+    builder: &'a mut lyon::path::path::Builder,
+    transform: &'a Transform,
+    /// Whether a contour was begun but not closed yet
+    open: bool,
+}
+
+impl OutlineSink<'_> {
+    fn map(&self, x: f32, y: f32) -> lyon::math::Point {
+        self.transform.transform_point(point(x, y))
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineSink<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        if self.open {
+            self.builder.end(false);
+        }
+
+        let to = self.map(x, y);
+        self.builder.begin(to);
+        self.open = true;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.map(x, y);
+        self.builder.line_to(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (ctrl, to) = (self.map(x1, y1), self.map(x, y));
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (ctrl1, ctrl2, to) = (self.map(x1, y1), self.map(x2, y2), self.map(x, y));
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+        self.open = false;
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{Easing, Group, Path, Smoothie};
+use std::ops::Range;
+
+/// What a **Reveal** staggers, one glyph after another or whole words at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevealUnit {
+    Glyph,
+    Word,
+}
+
+/// A staggered reveal of the glyphs of a text, each glyph fades, grows and moves from its start values into place
+#[derive(Debug, Clone)]
+pub struct Reveal {
+    /// Whether glyphs or words are revealed one after another
+    pub unit: RevealUnit,
+    /// Opacity the glyphs start at
+    pub opacity: f32,
+    /// Scale the glyphs start at, relative to their own
+    pub scale: f32,
+    /// Offset the glyphs start at, relative to their place
+    pub offset: [f32; 2],
+    /// Duration of the reveal of a single glyph or word
+    pub duration: f32,
+    /// Delay between the start of consecutive glyphs or words
+    pub lag: f32,
+    pub easing: Easing,
+}
+
+impl Default for Reveal {
+    /// Fades the glyphs in one after another, while they rise into place
+    fn default() -> Self {
+        Reveal {
+            unit: RevealUnit::Glyph,
+            opacity: 0.0,
+            scale: 1.0,
+            offset: [0.0, -0.1],
+            duration: 0.5,
+            lag: 0.05,
+            easing: Easing::EaseInOut,
+        }
+    }
+}
+
+/// The glyphs of a **Text** or **Math** as separate elements, see `Text::glyphs` and `Math::glyphs`
+///
+/// Animations on the glyphs are added as keyframes like with `animate!`, they start at the current animation time and advance it.
+pub struct TextGlyphs {
+    /// Group in place of the text, containing all glyphs
+    pub group: Group,
+    /// One **Path** per glyph in the order of the text, whitespace has no glyphs while bars of fractions and roots have their own
+    pub glyphs: Vec<Path>,
+    /// Ranges of glyphs that form a word
+    words: Vec<Range<usize>>,
+    /// Number of characters before each glyph as fraction of all characters, so typing keeps an even pace across spaces
+    typed_at: Vec<f32>,
+}
+
+impl TextGlyphs {
+    /// Groups the **glyphs** into words, **clusters** are the byte offsets of the glyphs in the **text**
+    pub(crate) fn new(group: Group, glyphs: Vec<Path>, text: &str, clusters: &[usize]) -> Self {
+        let mut words: Vec<Range<usize>> = vec![];
+
+        for (index, cluster) in clusters.iter().enumerate() {
+            // Glyphs belong to the same word unless there is whitespace between them
+            let joined = index > 0 && {
+                let previous = clusters[index - 1];
+                !text[previous.min(*cluster)..previous.max(*cluster)].contains(char::is_whitespace)
+            };
+
+            match words.last_mut() {
+                Some(word) if joined => word.end = index + 1,
+                _ => words.push(index..index + 1),
+            }
+        }
+
+        let characters = text.chars().count().max(1) as f32;
+        let typed_at = clusters
+            .iter()
+            .map(|cluster| text[..*cluster].chars().count() as f32 / characters)
+            .collect();
+
+        TextGlyphs {
+            group,
+            glyphs,
+            words,
+            typed_at,
+        }
+    }
+
+    /// Takes the **words** as given instead of finding them in a text, typing keeps an even pace across glyphs
+    pub(crate) fn from_words(group: Group, glyphs: Vec<Path>, words: Vec<Range<usize>>) -> Self {
+        let count = glyphs.len().max(1) as f32;
+        let typed_at = (0..glyphs.len())
+            .map(|index| index as f32 / count)
+            .collect();
+
+        TextGlyphs {
+            group,
+            glyphs,
+            words,
+            typed_at,
+        }
+    }
+
+    /// Number of words, separated by whitespace in texts, formulas have one per symbol, fraction, root or matrix at their top level
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns the glyphs of the word at the given **index**
+    pub fn word(&self, index: usize) -> Option<&[Path]> {
+        let range = self.words.get(index)?.clone();
+
+        Some(&self.glyphs[range])
+    }
+
+    /// Returns the glyphs of the word at the given **index** for animating them
+    pub fn word_mut(&mut self, index: usize) -> Option<&mut [Path]> {
+        let range = self.words.get(index)?.clone();
+
+        Some(&mut self.glyphs[range])
+    }
+
+    /// Types the text character by character within the **duration**, glyphs are hidden until their character is typed
+    pub fn type_in(&mut self, smoothie: &mut Smoothie, duration: f32) {
+        let start_time = smoothie.get_current_animation_time();
+        smoothie.add_element(&self.group);
+
+        for (glyph, typed_at) in self.glyphs.iter_mut().zip(&self.typed_at) {
+            glyph.add_keyframe((
+                |glyph: &mut Path, visible| glyph.visible = visible,
+                false,
+                true,
+                start_time + typed_at * duration,
+                0.0,
+                Easing::Linear,
+            ));
+            glyph.visible = true;
+
+            smoothie.add_element(glyph);
+        }
+
+        smoothie.increment_animation_time(duration);
+    }
+
+    /// Reveals the glyphs or words one after another, each starting **lag** seconds after the previous one
+    pub fn reveal(&mut self, smoothie: &mut Smoothie, reveal: &Reveal) {
+        let units: Vec<Range<usize>> = match reveal.unit {
+            RevealUnit::Glyph => (0..self.glyphs.len())
+                .map(|index| index..index + 1)
+                .collect(),
+            RevealUnit::Word => self.words.clone(),
+        };
+
+        let start_time = smoothie.get_current_animation_time();
+        smoothie.add_element(&self.group);
+
+        for (index, range) in units.iter().enumerate() {
+            let unit_start_time = start_time + index as f32 * reveal.lag;
+
+            for glyph in &mut self.glyphs[range.clone()] {
+                reveal_glyph(glyph, reveal, unit_start_time);
+                smoothie.add_element(glyph);
+            }
+        }
+
+        if !units.is_empty() {
+            smoothie
+                .increment_animation_time((units.len() - 1) as f32 * reveal.lag + reveal.duration);
+        }
+    }
+}
+
+/// Adds the keyframes that move a **glyph** from the start values of the **reveal** to its current values
+fn reveal_glyph(glyph: &mut Path, reveal: &Reveal, start_time: f32) {
+    let (duration, easing) = (reveal.duration, reveal.easing.clone());
+
+    glyph.add_keyframe((
+        |glyph: &mut Path, opacity| glyph.opacity = opacity,
+        reveal.opacity,
+        glyph.opacity,
+        start_time,
+        duration,
+        easing.clone(),
+    ));
+    glyph.add_keyframe((
+        |glyph: &mut Path, scale| glyph.scale = scale,
+        reveal.scale * glyph.scale,
+        glyph.scale,
+        start_time,
+        duration,
+        easing.clone(),
+    ));
+    glyph.add_keyframe((
+        |glyph: &mut Path, x| glyph.x = x,
+        glyph.x + reveal.offset[0],
+        glyph.x,
+        start_time,
+        duration,
+        easing.clone(),
+    ));
+    glyph.add_keyframe((
+        |glyph: &mut Path, y| glyph.y = y,
+        glyph.y + reveal.offset[1],
+        glyph.y,
+        start_time,
+        duration,
+        easing,
+    ));
+}
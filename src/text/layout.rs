@@ -0,0 +1,413 @@
+use crate::text::font::append_glyph_outline;
+use crate::text::Font;
+use lyon::math::{point, vector, Point, Transform};
+use lyon::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Horizontal alignment of the lines of a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    /// Lines start at the origin
+    Left,
+    /// Lines are centered on the origin
+    Center,
+    /// Lines end at the origin
+    Right,
+}
+
+/// Settings for laying out a text, lengths are in scene units
+#[derive(Clone, PartialEq)]
+pub(crate) struct LayoutOptions {
+    pub font_size: f32,
+    /// Distance between baselines as a multiple of the **font_size**
+    pub line_height: f32,
+    pub align: TextAlign,
+    /// Lines are broken between words to stay within this width
+    pub max_width: Option<f32>,
+    /// Horizontal offset of the first line
+    pub indent: f32,
+    /// Whether all digits take the same width, so changing numbers keep their width
+    pub tabular_digits: bool,
+}
+
+/// A glyph placed by the layout, relative to the origin of the text
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PositionedGlyph {
+    pub glyph_id: u16,
+    /// Position of the glyph origin on the baseline
+    pub x: f32,
+    pub y: f32,
+    pub advance: f32,
+    /// Byte offset of the first character of the glyph in the text
+    pub cluster: usize,
+}
+
+/// Extent of a line placed by the layout, trailing whitespace is not included
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LayoutLine {
+    pub start: f32,
+    pub end: f32,
+    pub baseline: f32,
+}
+
+/// What a text is laid out from, a layout is reused as long as none of it changes
+#[derive(Clone)]
+pub(crate) struct LayoutKey {
+    pub text: String,
+    pub font: Font,
+    pub options: LayoutOptions,
+}
+
+impl PartialEq for LayoutKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.font.same_as(&other.font) && self.options == other.options
+    }
+}
+
+/// Remembers the last layout built by a text element, so an unchanged text is not laid out again every frame
+///
+/// Clones of the element share the cache, the scene hands a clone to the renderer every frame.
+pub(crate) struct LayoutCache<T> {
+    last: Arc<Mutex<Option<CachedLayout<T>>>>,
+}
+
+/// A layout in the **LayoutCache** together with what it was built from
+struct CachedLayout<T> {
+    key: LayoutKey,
+    layout: Arc<T>,
+}
+
+impl<T> LayoutCache<T> {
+    /// Returns the layout for the **key**, building it only if the key changed since the last call
+    pub fn get_or_build(&self, key: LayoutKey, build: impl FnOnce(&LayoutKey) -> T) -> Arc<T> {
+        // A panic while building poisons the cache, later layouts are built without it
+        let Ok(mut last) = self.last.lock() else {
+            return Arc::new(build(&key));
+        };
+
+        match last.as_ref() {
+            Some(cached) if cached.key == key => cached.layout.clone(),
+            _ => {
+                let layout = Arc::new(build(&key));
+                *last = Some(CachedLayout {
+                    key,
+                    layout: layout.clone(),
+                });
+                layout
+            }
+        }
+    }
+}
+
+impl<T> Clone for LayoutCache<T> {
+    fn clone(&self) -> Self {
+        LayoutCache {
+            last: self.last.clone(),
+        }
+    }
+}
+
+impl<T> Default for LayoutCache<T> {
+    fn default() -> Self {
+        LayoutCache {
+            last: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Glyphs of a text laid out in lines
+pub(crate) struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub lines: Vec<LayoutLine>,
+    /// Position of the pen after the last glyph, where following text would continue
+    pub end: Point,
+    /// Scale from font units to scene units
+    pub scale: f32,
+}
+
+/// A shaped glyph before it is placed on a line, in font units
+struct ShapedGlyph {
+    glyph_id: u16,
+    cluster: usize,
+    advance: f32,
+    offset_x: f32,
+    offset_y: f32,
+    whitespace: bool,
+    digit: bool,
+}
+
+/// Shapes the **text** with kerning and ligatures of the **font** and breaks it into lines
+///
+/// The baseline of the first line is at y = 0, following lines go downwards. Lines break at `\n` and,
+/// with a maximum width, at the last space that keeps the line within it; a single word wider than that keeps its own line.
+pub(crate) fn layout(font: &Font, text: &str, options: &LayoutOptions) -> TextLayout {
+    let face = font.face();
+    let scale = options.font_size / face.units_per_em() as f32;
+    let max_width = options
+        .max_width
+        .map_or(f32::INFINITY, |width| width / scale);
+
+    let mut glyphs = vec![];
+    let mut lines = vec![];
+    let mut end = Point::zero();
+    let mut paragraph_start = 0;
+
+    for paragraph in text.split('\n') {
+        let mut shaped = shape(&face, paragraph, paragraph_start);
+        if options.tabular_digits {
+            tabulate_digits(&face, &mut shaped);
+        }
+
+        // Only the first line is indented, which leaves less room for it
+        let first_line_indent = if lines.is_empty() {
+            options.indent
+        } else {
+            0.0
+        };
+
+        for range in break_lines(&shaped, max_width, first_line_indent / scale) {
+            let line_glyphs = &shaped[range];
+            let indent = if lines.is_empty() {
+                options.indent
+            } else {
+                0.0
+            };
+
+            // Trailing spaces do not count towards the width used for alignment
+            let width: f32 = line_glyphs
+                .iter()
+                .rev()
+                .skip_while(|glyph| glyph.whitespace)
+                .map(|glyph| glyph.advance)
+                .sum();
+            let line_start = match options.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => -width / 2.0,
+                TextAlign::Right => -width,
+            };
+            let baseline = -(lines.len() as f32) * options.line_height * options.font_size;
+
+            let mut pen = line_start;
+            for glyph in line_glyphs {
+                glyphs.push(PositionedGlyph {
+                    glyph_id: glyph.glyph_id,
+                    x: (pen + glyph.offset_x) * scale + indent,
+                    y: baseline + glyph.offset_y * scale,
+                    advance: glyph.advance * scale,
+                    cluster: glyph.cluster,
+                });
+                pen += glyph.advance;
+            }
+
+            lines.push(LayoutLine {
+                start: line_start * scale + indent,
+                end: (line_start + width) * scale + indent,
+                baseline,
+            });
+            end = point(pen * scale + indent, baseline);
+        }
+
+        paragraph_start += paragraph.len() + 1;
+    }
+
+    TextLayout {
+        glyphs,
+        lines,
+        end,
+        scale,
+    }
+}
+
+impl TextLayout {
+    /// Builds the outlines of all glyphs into a single path
+    pub fn build_path(&self, font: &Font) -> Path {
+        let face = font.face();
+        let mut builder = Path::builder();
+
+        for glyph in &self.glyphs {
+            append_glyph_outline(
+                &face,
+                glyph.glyph_id,
+                &Transform::scale(self.scale, self.scale).then_translate(vector(glyph.x, glyph.y)),
+                &mut builder,
+            );
+        }
+
+        builder.build()
+    }
+
+    /// Builds the outline of a single **glyph** around its own origin instead of its place in the text
+    pub fn build_glyph_path(&self, face: &ttf_parser::Face<'_>, glyph: &PositionedGlyph) -> Path {
+        let mut builder = Path::builder();
+        append_glyph_outline(
+            face,
+            glyph.glyph_id,
+            &Transform::scale(self.scale, self.scale),
+            &mut builder,
+        );
+        builder.build()
+    }
+}
+
+/// Shapes a single line of text, clusters are offset by **start** to index the whole text
+fn shape(face: &rustybuzz::Face<'_>, text: &str, start: usize) -> Vec<ShapedGlyph> {
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    let output = rustybuzz::shape(face, &[], buffer);
+
+    output
+        .glyph_infos()
+        .iter()
+        .zip(output.glyph_positions())
+        .map(|(info, position)| {
+            let character = text[info.cluster as usize..].chars().next();
+
+            ShapedGlyph {
+                glyph_id: info.glyph_id as u16,
+                cluster: start + info.cluster as usize,
+                advance: position.x_advance as f32,
+                offset_x: position.x_offset as f32,
+                offset_y: position.y_offset as f32,
+                whitespace: character.is_some_and(char::is_whitespace),
+                digit: character.is_some_and(|character| character.is_ascii_digit()),
+            }
+        })
+        .collect()
+}
+
+/// Gives all digits the advance of the widest digit and centers them within it
+fn tabulate_digits(face: &rustybuzz::Face<'_>, glyphs: &mut [ShapedGlyph]) {
+    let advance = |glyph_id: u16| {
+        face.glyph_hor_advance(ttf_parser::GlyphId(glyph_id))
+            .unwrap_or(0) as f32
+    };
+    let digit_width = ('0'..='9')
+        .filter_map(|digit| face.glyph_index(digit))
+        .map(|glyph_id| advance(glyph_id.0))
+        .fold(0.0, f32::max);
+
+    for glyph in glyphs.iter_mut().filter(|glyph| glyph.digit) {
+        glyph.offset_x += (digit_width - advance(glyph.glyph_id)) / 2.0;
+        glyph.advance = digit_width;
+    }
+}
+
+/// Breaks shaped glyphs into ranges of lines, greedily filling each line up to the **max_width** in font units
+///
+/// The first line starts at the **indent** instead of 0.
+fn break_lines(glyphs: &[ShapedGlyph], max_width: f32, indent: f32) -> Vec<std::ops::Range<usize>> {
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut width = indent;
+    let mut last_space = None;
+
+    for (index, glyph) in glyphs.iter().enumerate() {
+        if glyph.whitespace {
+            last_space = Some(index);
+        } else if width + glyph.advance > max_width {
+            // The line ends before the last space, which is dropped, the rest of the word moves to the next line
+            if let Some(space) = last_space.take() {
+                lines.push(start..space);
+                start = space + 1;
+                width = glyphs[start..index].iter().map(|glyph| glyph.advance).sum();
+            }
+        }
+
+        width += glyph.advance;
+    }
+
+    lines.push(start..glyphs.len());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn key(text: &str, font_size: f32) -> LayoutKey {
+        LayoutKey {
+            text: text.to_string(),
+            font: Font::default(),
+            options: LayoutOptions {
+                font_size,
+                line_height: 1.2,
+                align: TextAlign::Left,
+                max_width: None,
+                indent: 0.0,
+                tabular_digits: false,
+            },
+        }
+    }
+
+    /// Returns one glyph per character of the **text**, each one unit wide
+    fn glyphs(text: &str) -> Vec<ShapedGlyph> {
+        text.char_indices()
+            .map(|(cluster, character)| ShapedGlyph {
+                glyph_id: 0,
+                cluster,
+                advance: 1.0,
+                offset_x: 0.0,
+                offset_y: 0.0,
+                whitespace: character == ' ',
+                digit: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lines_break_at_the_last_space_within_the_max_width() {
+        assert_eq!(break_lines(&glyphs("aa bb cc"), 5.0, 0.0), vec![0..5, 6..8]);
+        assert_eq!(
+            break_lines(&glyphs("aa bb cc"), 4.0, 0.0),
+            vec![0..2, 3..5, 6..8]
+        );
+        assert_eq!(
+            break_lines(&glyphs("aa bb cc"), f32::INFINITY, 0.0),
+            vec![0..8]
+        );
+    }
+
+    #[test]
+    fn words_wider_than_the_max_width_keep_their_own_line() {
+        assert_eq!(
+            break_lines(&glyphs("aaaaaaa bb"), 3.0, 0.0),
+            vec![0..7, 8..10]
+        );
+        assert_eq!(
+            break_lines(&glyphs("bb aaaaaaa"), 3.0, 0.0),
+            vec![0..2, 3..10]
+        );
+    }
+
+    #[test]
+    fn the_indent_leaves_less_room_on_the_first_line() {
+        assert_eq!(break_lines(&glyphs("aa bb cc"), 5.0, 2.0), vec![0..2, 3..8]);
+        assert_eq!(break_lines(&glyphs("aa bb cc"), 5.0, 4.0), vec![0..2, 3..8]);
+    }
+
+    #[test]
+    fn layouts_are_only_built_again_when_their_key_changes() {
+        let cache = LayoutCache::default();
+        let builds = Cell::new(0);
+        let build = |key: &LayoutKey| {
+            builds.set(builds.get() + 1);
+            key.text.clone()
+        };
+
+        let first = cache.get_or_build(key("1.0", 0.5), build);
+        let again = cache.clone().get_or_build(key("1.0", 0.5), build);
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(builds.get(), 1);
+
+        cache.get_or_build(key("2.0", 0.5), build);
+        cache.get_or_build(key("2.0", 0.6), build);
+        assert_eq!(builds.get(), 3);
+
+        // Changing the font lays the text out again as well
+        let mut other_font = key("2.0", 0.6);
+        other_font.font = Font::default_math();
+        assert_eq!(*cache.get_or_build(other_font, build), "2.0");
+        assert_eq!(builds.get(), 4);
+    }
+}
@@ -0,0 +1,599 @@
+use crate::text::font::append_glyph_outline;
+use crate::text::math::parser::{Class, Node};
+use lyon::math::{point, vector, Transform};
+use lyon::path::Path;
+use std::ops::Range;
+
+/// Height of the math axis above the baseline, fractions, operators and delimiters are centered on it
+const AXIS_HEIGHT: f32 = 0.25;
+
+/// Thickness of fraction bars and the bar of roots
+const RULE_THICKNESS: f32 = 0.05;
+
+/// Size of scripts and nested fractions relative to their parent
+const SCRIPT_SCALE: f32 = 0.7;
+
+/// Scripts of scripts don't shrink below this size, so they stay readable
+const MIN_SCALE: f32 = 0.5;
+
+/// A piece of a laid out formula, lengths are in em
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Item {
+    /// A glyph with its origin at (x, y), scaled to **size** and stretched vertically by **stretch**
+    Glyph {
+        glyph_id: u16,
+        x: f32,
+        y: f32,
+        size: f32,
+        stretch: f32,
+    },
+    /// A filled rectangle like a fraction bar, (x, y) is its bottom left corner
+    Rule {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+/// A laid out formula or part of it, the origin is on the baseline at the left, lengths are in em
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MathBox {
+    pub width: f32,
+    /// Extent above the baseline
+    pub ascent: f32,
+    /// Extent below the baseline
+    pub descent: f32,
+    pub items: Vec<Item>,
+    /// Ranges of the items of each atom, only for boxes laid out from a list
+    pub atoms: Vec<Range<usize>>,
+}
+
+impl MathBox {
+    /// Places the items of **other** with its origin at (x, y), the width is left to the caller
+    fn append(&mut self, other: MathBox, x: f32, y: f32) {
+        self.ascent = self.ascent.max(other.ascent + y);
+        self.descent = self.descent.max(other.descent - y);
+        self.items
+            .extend(other.items.into_iter().map(|item| match item {
+                Item::Glyph {
+                    glyph_id,
+                    x: glyph_x,
+                    y: glyph_y,
+                    size,
+                    stretch,
+                } => Item::Glyph {
+                    glyph_id,
+                    x: glyph_x + x,
+                    y: glyph_y + y,
+                    size,
+                    stretch,
+                },
+                Item::Rule {
+                    x: rule_x,
+                    y: rule_y,
+                    width,
+                    height,
+                } => Item::Rule {
+                    x: rule_x + x,
+                    y: rule_y + y,
+                    width,
+                    height,
+                },
+            }));
+    }
+
+    /// Places **other** after the current content and extends the width by it
+    fn push(&mut self, other: MathBox) {
+        let x = self.width;
+        self.width += other.width;
+        self.append(other, x, 0.0);
+    }
+
+    /// Builds the outline of a single **item**, scaled from em to the **font_size** and moved by **offset_x** em
+    pub fn build_item_path(
+        face: &ttf_parser::Face<'_>,
+        item: &Item,
+        font_size: f32,
+        offset_x: f32,
+    ) -> Path {
+        let mut builder = Path::builder();
+
+        match *item {
+            Item::Glyph {
+                glyph_id,
+                x,
+                y,
+                size,
+                stretch,
+            } => {
+                let scale = size * font_size / face.units_per_em() as f32;
+                let transform = Transform::scale(scale, scale * stretch)
+                    .then_translate(vector((x + offset_x) * font_size, y * font_size));
+                append_glyph_outline(face, glyph_id, &transform, &mut builder);
+            }
+            Item::Rule {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let (left, bottom) = ((x + offset_x) * font_size, y * font_size);
+                let (right, top) = (left + width * font_size, bottom + height * font_size);
+
+                // Clockwise like the outer contours of TrueType glyphs, so overlapping glyphs merge with it
+                builder.begin(point(left, top));
+                builder.line_to(point(right, top));
+                builder.line_to(point(right, bottom));
+                builder.line_to(point(left, bottom));
+                builder.close();
+            }
+        }
+
+        builder.build()
+    }
+}
+
+/// Size and kind of the layout, formulas start in display style and nested parts use the smaller styles
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    size: f32,
+    /// Large operators grow and fractions stay at full size in display style
+    display: bool,
+}
+
+impl Style {
+    /// Style of a superscript or subscript
+    fn script(self) -> Self {
+        Style {
+            size: (self.size * SCRIPT_SCALE).max(MIN_SCALE),
+            display: false,
+        }
+    }
+
+    /// Style of the numerator and denominator of a fraction
+    fn fraction(self) -> Self {
+        if self.display {
+            Style {
+                display: false,
+                ..self
+            }
+        } else {
+            self.script()
+        }
+    }
+}
+
+/// Lays out a parsed formula in display style, lengths are in em
+pub(crate) fn layout(face: &ttf_parser::Face<'_>, nodes: &[Node]) -> MathBox {
+    let layouter = Layouter { face };
+
+    layouter.list(
+        nodes,
+        Style {
+            size: 1.0,
+            display: true,
+        },
+    )
+}
+
+struct Layouter<'a> {
+    face: &'a ttf_parser::Face<'a>,
+}
+
+impl Layouter<'_> {
+    /// Lays out atoms one after another, with the spacing TeX puts between their classes
+    fn list(&self, nodes: &[Node], style: Style) -> MathBox {
+        let mut result = MathBox::default();
+        let mut previous: Option<Class> = None;
+
+        for node in nodes {
+            // Binary operators without a left operand, like a leading minus, are ordinary symbols
+            let class = node.class().map(|class| match (class, previous) {
+                (
+                    Class::Binary,
+                    None
+                    | Some(
+                        Class::Binary
+                        | Class::Operator
+                        | Class::Relation
+                        | Class::Open
+                        | Class::Punctuation,
+                    ),
+                ) => Class::Ordinary,
+                _ => class,
+            });
+
+            if let (Some(left), Some(right)) = (previous, class) {
+                result.width += spacing(left, right, style);
+            }
+
+            let start = result.items.len();
+            result.push(self.node(node, style));
+            result.atoms.push(start..result.items.len());
+
+            if class.is_some() {
+                previous = class;
+            }
+        }
+
+        result
+    }
+
+    fn node(&self, node: &Node, style: Style) -> MathBox {
+        match node {
+            Node::Symbol { char, .. } => self.glyph(*char, style.size),
+            Node::Operator { text, large, .. } if *large => {
+                let size = if style.display { 1.4 } else { 1.0 } * style.size;
+                let mut result = MathBox::default();
+                result.push(self.text(text, size));
+
+                // Large operators are centered on the axis instead of sitting on the baseline
+                let shift = AXIS_HEIGHT * style.size - (result.ascent - result.descent) / 2.0;
+                let mut centered = MathBox {
+                    width: result.width,
+                    ..MathBox::default()
+                };
+                centered.append(result, 0.0, shift);
+                centered
+            }
+            Node::Operator { text, .. } | Node::Text(text) => self.text(text, style.size),
+            Node::Group(nodes) => self.list(nodes, style),
+            Node::Scripts {
+                base,
+                superscript,
+                subscript,
+            } => self.scripts(base, superscript.as_deref(), subscript.as_deref(), style),
+            Node::Fraction {
+                numerator,
+                denominator,
+            } => self.fraction(numerator, denominator, style),
+            Node::Root { index, radicand } => self.root(index.as_deref(), radicand, style),
+            Node::Delimited {
+                open,
+                close,
+                content,
+            } => self.delimited(*open, *close, self.list(content, style), style),
+            Node::Matrix {
+                open,
+                close,
+                rows,
+                left_aligned,
+            } => {
+                let matrix = self.matrix(rows, *left_aligned, style);
+                self.delimited(*open, *close, matrix, style)
+            }
+            Node::Space(width) => MathBox {
+                width: width * style.size,
+                ..MathBox::default()
+            },
+        }
+    }
+
+    /// Lays out a single character, characters missing in the font take no space
+    fn glyph(&self, char: char, size: f32) -> MathBox {
+        let Some(glyph_id) = self.face.glyph_index(char) else {
+            return MathBox::default();
+        };
+        let scale = size / self.face.units_per_em() as f32;
+        let (ascent, descent) = self
+            .face
+            .glyph_bounding_box(glyph_id)
+            .map_or((0.0, 0.0), |rect| {
+                (rect.y_max as f32 * scale, -rect.y_min as f32 * scale)
+            });
+
+        MathBox {
+            width: self.face.glyph_hor_advance(glyph_id).unwrap_or(0) as f32 * scale,
+            ascent,
+            descent,
+            items: vec![Item::Glyph {
+                glyph_id: glyph_id.0,
+                x: 0.0,
+                y: 0.0,
+                size,
+                stretch: 1.0,
+            }],
+            atoms: vec![],
+        }
+    }
+
+    /// Lays out upright text character by character
+    fn text(&self, text: &str, size: f32) -> MathBox {
+        let mut result = MathBox::default();
+
+        for char in text.chars() {
+            result.push(self.glyph(char, size));
+        }
+
+        result
+    }
+
+    /// Lays out a glyph stretched vertically to at least the **height**, centered on **center**
+    fn stretched_glyph(&self, char: char, size: f32, height: f32, center: f32) -> MathBox {
+        let mut result = self.glyph(char, size);
+        let natural = result.ascent + result.descent;
+        if natural <= 0.0 {
+            return result;
+        }
+
+        let stretch = (height / natural).max(1.0);
+        let shift = center - (result.ascent - result.descent) * stretch / 2.0;
+
+        for item in &mut result.items {
+            if let Item::Glyph {
+                y, stretch: factor, ..
+            } = item
+            {
+                *y = shift;
+                *factor = stretch;
+            }
+        }
+        result.ascent = result.ascent * stretch + shift;
+        result.descent = result.descent * stretch - shift;
+        result
+    }
+
+    /// Sets scripts to the right of the **base**, or above and below it for operators with limits in display style
+    fn scripts(
+        &self,
+        base: &Node,
+        superscript: Option<&Node>,
+        subscript: Option<&Node>,
+        style: Style,
+    ) -> MathBox {
+        let s = style.size;
+        let base_box = self.node(base, style);
+        let superscript = superscript.map(|node| self.node(node, style.script()));
+        let subscript = subscript.map(|node| self.node(node, style.script()));
+
+        if style.display && matches!(base, Node::Operator { limits: true, .. }) {
+            let width = [&superscript, &subscript]
+                .into_iter()
+                .flatten()
+                .map(|script| script.width)
+                .fold(base_box.width, f32::max);
+            let (base_ascent, base_descent) = (base_box.ascent, base_box.descent);
+            let mut result = MathBox {
+                width,
+                ..MathBox::default()
+            };
+
+            let center = |inner: &MathBox| (width - inner.width) / 2.0;
+            result.append(base_box.clone(), center(&base_box), 0.0);
+            if let Some(superscript) = superscript {
+                let (x, y) = (
+                    center(&superscript),
+                    base_ascent + 0.15 * s + superscript.descent,
+                );
+                result.append(superscript, x, y);
+            }
+            if let Some(subscript) = subscript {
+                let (x, y) = (
+                    center(&subscript),
+                    -(base_descent + 0.15 * s + subscript.ascent),
+                );
+                result.append(subscript, x, y);
+            }
+
+            return result;
+        }
+
+        // Scripts follow tall bases like fractions, but keep a minimum shift for small ones like letters
+        let mut shift_up = (base_box.ascent - 0.25 * s).max(0.4 * s);
+        let mut shift_down = (base_box.descent + 0.05 * s).max(0.15 * s);
+
+        if let (Some(superscript), Some(subscript)) = (&superscript, &subscript) {
+            shift_down = shift_down.max(0.25 * s);
+            let gap = (shift_up - superscript.descent) - (subscript.ascent - shift_down);
+            if gap < 0.2 * s {
+                shift_up += (0.2 * s - gap) / 2.0;
+                shift_down += (0.2 * s - gap) / 2.0;
+            }
+        }
+
+        let x = base_box.width;
+        let mut result = MathBox::default();
+        result.push(base_box);
+
+        let mut scripts_width: f32 = 0.0;
+        if let Some(superscript) = superscript {
+            scripts_width = scripts_width.max(superscript.width);
+            result.append(superscript, x, shift_up);
+        }
+        if let Some(subscript) = subscript {
+            scripts_width = scripts_width.max(subscript.width);
+            result.append(subscript, x, -shift_down);
+        }
+
+        // A little space after the scripts like TeX's \scriptspace
+        result.width += scripts_width + 0.05 * s;
+        result
+    }
+
+    /// Centers the numerator above and the denominator below a bar on the axis
+    fn fraction(&self, numerator: &[Node], denominator: &[Node], style: Style) -> MathBox {
+        let s = style.size;
+        let numerator = self.list(numerator, style.fraction());
+        let denominator = self.list(denominator, style.fraction());
+
+        let (axis, rule) = (AXIS_HEIGHT * s, RULE_THICKNESS * s);
+        let gap = if style.display { 0.15 * s } else { 0.1 * s };
+        let padding = 0.1 * s;
+        let width = numerator.width.max(denominator.width) + 2.0 * padding;
+
+        let mut result = MathBox {
+            width,
+            ..MathBox::default()
+        };
+        let (numerator_width, denominator_width) = (numerator.width, denominator.width);
+        let numerator_y = axis + rule / 2.0 + gap + numerator.descent;
+        let denominator_y = axis - rule / 2.0 - gap - denominator.ascent;
+        result.append(numerator, (width - numerator_width) / 2.0, numerator_y);
+        result.append(
+            denominator,
+            (width - denominator_width) / 2.0,
+            denominator_y,
+        );
+        result.items.push(Item::Rule {
+            x: padding / 2.0,
+            y: axis - rule / 2.0,
+            width: width - padding,
+            height: rule,
+        });
+
+        result
+    }
+
+    /// Covers the **radicand** with a radical sign stretched to its height and a bar, the **index** sits in the notch
+    fn root(&self, index: Option<&[Node]>, radicand: &[Node], style: Style) -> MathBox {
+        let s = style.size;
+        let radicand = self.list(radicand, style);
+        let rule = RULE_THICKNESS * s;
+
+        // The top of the radical sign lines up with the top of the bar
+        let top = radicand.ascent.max(0.7 * s) + 0.15 * s + rule;
+        let bottom = radicand.descent.max(0.2 * s) + 0.05 * s;
+        let radical = self.stretched_glyph('√', s, top + bottom, (top - bottom) / 2.0);
+        let radical_width = radical.width;
+
+        // The radical sign shifts right to make room for an index wider than its notch
+        let mut result = MathBox::default();
+        let mut offset = 0.0;
+        if let Some(index) = index {
+            let index = self.list(index, style.script().script());
+            offset = (index.width - 0.5 * radical_width).max(0.0);
+            let y = (top - bottom) * 0.5 + index.descent;
+            let x = offset + 0.5 * radical_width - index.width;
+            result.append(index, x.max(0.0), y);
+        }
+
+        // The bar meets the actual top of the sign, which is higher than planned if it didn't need to stretch
+        let radical_top = radical.ascent;
+        result.append(radical, offset, 0.0);
+
+        let bar_width = radicand.width + 0.1 * s;
+        result.items.push(Item::Rule {
+            x: offset + radical_width - rule / 2.0,
+            y: radical_top - rule,
+            width: bar_width + rule / 2.0,
+            height: rule,
+        });
+        result.append(radicand, offset + radical_width + 0.05 * s, 0.0);
+        result.width = offset + radical_width + bar_width;
+
+        result
+    }
+
+    /// Surrounds the **content** with delimiters stretched to its height around the axis
+    fn delimited(
+        &self,
+        open: Option<char>,
+        close: Option<char>,
+        content: MathBox,
+        style: Style,
+    ) -> MathBox {
+        let s = style.size;
+        let axis = AXIS_HEIGHT * s;
+
+        // Delimiters are symmetric around the axis, so they cover the farther extent on both sides
+        let half = (content.ascent - axis).max(content.descent + axis);
+        let height = 2.0 * half + 0.1 * s;
+
+        let mut result = MathBox::default();
+        if let Some(open) = open {
+            result.push(self.stretched_glyph(open, s, height, axis));
+        }
+        result.push(content);
+        if let Some(close) = close {
+            result.push(self.stretched_glyph(close, s, height, axis));
+        }
+
+        result
+    }
+
+    /// Lays out cells in aligned rows and columns, the whole matrix is centered on the axis
+    fn matrix(&self, rows: &[Vec<Vec<Node>>], left_aligned: bool, style: Style) -> MathBox {
+        let s = style.size;
+        let cell_style = Style {
+            display: false,
+            ..style
+        };
+        let cells: Vec<Vec<MathBox>> = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| self.list(cell, cell_style)).collect())
+            .collect();
+
+        let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+        let column_widths: Vec<f32> = (0..columns)
+            .map(|column| {
+                cells
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.width)
+                    .fold(0.0, f32::max)
+            })
+            .collect();
+        let (column_gap, row_gap) = (0.8 * s, 0.25 * s);
+
+        // Rows keep a minimum height, so rows of small symbols are spaced evenly
+        let extent = |row: &Vec<MathBox>| {
+            row.iter()
+                .fold((0.7 * s, 0.3 * s), |(ascent, descent), cell| {
+                    (ascent.max(cell.ascent), descent.max(cell.descent))
+                })
+        };
+
+        let mut baselines = vec![];
+        let mut y = 0.0;
+        let mut previous_descent = None;
+        for row in &cells {
+            let (ascent, descent) = extent(row);
+            if let Some(previous_descent) = previous_descent {
+                y -= previous_descent + row_gap + ascent;
+            }
+            baselines.push(y);
+            previous_descent = Some(descent);
+        }
+
+        let top = cells.first().map_or(0.0, |row| extent(row).0);
+        let bottom = cells.last().map_or(0.0, |row| extent(row).1) - y;
+        let shift = AXIS_HEIGHT * s - (top - bottom) / 2.0;
+
+        let mut result = MathBox {
+            width: column_widths.iter().sum::<f32>()
+                + column_gap * columns.saturating_sub(1) as f32,
+            ..MathBox::default()
+        };
+        for (row, baseline) in cells.into_iter().zip(baselines) {
+            let mut x = 0.0;
+            for (cell, column_width) in row.into_iter().zip(&column_widths) {
+                let cell_x = if left_aligned {
+                    x
+                } else {
+                    x + (column_width - cell.width) / 2.0
+                };
+                result.append(cell, cell_x, baseline + shift);
+                x += column_width + column_gap;
+            }
+        }
+
+        result
+    }
+}
+
+/// Returns the space between atoms of the given classes in em, only thin spaces remain in scripts
+fn spacing(left: Class, right: Class, style: Style) -> f32 {
+    let script = style.size < 1.0;
+    let eighteenths = match (left, right) {
+        (Class::Relation, Class::Relation) => 0.0,
+        (Class::Relation, _) | (_, Class::Relation) if !script => 5.0,
+        (Class::Binary, _) | (_, Class::Binary) if !script => 4.0,
+        (Class::Operator, Class::Ordinary | Class::Operator)
+        | (Class::Ordinary | Class::Close, Class::Operator) => 3.0,
+        (Class::Punctuation, _) if !script => 3.0,
+        _ => 0.0,
+    };
+
+    eighteenths / 18.0 * style.size
+}
@@ -0,0 +1,37 @@
+mod layout;
+mod parser;
+
+pub(crate) use layout::{layout, MathBox};
+pub(crate) use parser::{parse, Node};
+
+/// Error while parsing a formula, see `Math::try_tex`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MathError {
+    /// A `{` without `}` or the other way around
+    UnbalancedBraces,
+    /// A command that is not part of the supported subset
+    UnknownCommand(String),
+    /// An environment other than the matrix environments and `cases`
+    UnknownEnvironment(String),
+    /// A command or script is missing its argument
+    MissingArgument(String),
+    /// A `\left`, `\begin` or `[` is not closed
+    Unclosed(String),
+    /// A token where it is not allowed, like `&` outside of a matrix or a second superscript
+    Unexpected(String),
+}
+
+impl std::fmt::Display for MathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MathError::UnbalancedBraces => write!(f, "Unbalanced braces in formula"),
+            MathError::UnknownCommand(command) => write!(f, "Unknown command {}", command),
+            MathError::UnknownEnvironment(name) => write!(f, "Unknown environment {}", name),
+            MathError::MissingArgument(command) => write!(f, "Missing argument for {}", command),
+            MathError::Unclosed(token) => write!(f, "{} is not closed", token),
+            MathError::Unexpected(token) => write!(f, "Unexpected {} in formula", token),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
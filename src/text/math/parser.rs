@@ -0,0 +1,704 @@
+use crate::text::math::MathError;
+
+/// Spacing class of an atom, the space between neighbouring atoms depends on their classes like in TeX
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Class {
+    Ordinary,
+    /// Large operators and function names like `\sum` or `\sin`
+    Operator,
+    /// Binary operators like `+`
+    Binary,
+    /// Relations like `=`
+    Relation,
+    Open,
+    Close,
+    Punctuation,
+}
+
+/// A node of a parsed formula
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    /// A single character of the font
+    Symbol {
+        char: char,
+        class: Class,
+    },
+    /// A function name like `sin` or a **large** operator like `∑`, with **limits** scripts are set above and below
+    Operator {
+        text: String,
+        large: bool,
+        limits: bool,
+    },
+    /// Upright text, from `\text`
+    Text(String),
+    Group(Vec<Node>),
+    Scripts {
+        base: Box<Node>,
+        superscript: Option<Box<Node>>,
+        subscript: Option<Box<Node>>,
+    },
+    Fraction {
+        numerator: Vec<Node>,
+        denominator: Vec<Node>,
+    },
+    Root {
+        index: Option<Vec<Node>>,
+        radicand: Vec<Node>,
+    },
+    /// Content between delimiters that grow with it, from `\left` and `\right`
+    Delimited {
+        open: Option<char>,
+        close: Option<char>,
+        content: Vec<Node>,
+    },
+    /// Rows of cells between delimiters, from the matrix environments, cells are centered in their column unless **left_aligned**
+    Matrix {
+        open: Option<char>,
+        close: Option<char>,
+        rows: Vec<Vec<Vec<Node>>>,
+        left_aligned: bool,
+    },
+    /// Horizontal space in em
+    Space(f32),
+}
+
+impl Node {
+    /// Returns the spacing class, spaces have none and don't affect the spacing of their neighbours
+    pub fn class(&self) -> Option<Class> {
+        match self {
+            Node::Symbol { class, .. } => Some(*class),
+            Node::Operator { .. } => Some(Class::Operator),
+            Node::Scripts { base, .. } => base.class(),
+            Node::Space(_) => None,
+            _ => Some(Class::Ordinary),
+        }
+    }
+}
+
+/// Symbols available as commands, along with their class
+const SYMBOLS: &[(&str, char, Class)] = &[
+    ("alpha", 'α', Class::Ordinary),
+    ("beta", 'β', Class::Ordinary),
+    ("gamma", 'γ', Class::Ordinary),
+    ("delta", 'δ', Class::Ordinary),
+    ("epsilon", 'ϵ', Class::Ordinary),
+    ("varepsilon", 'ε', Class::Ordinary),
+    ("zeta", 'ζ', Class::Ordinary),
+    ("eta", 'η', Class::Ordinary),
+    ("theta", 'θ', Class::Ordinary),
+    ("vartheta", 'ϑ', Class::Ordinary),
+    ("iota", 'ι', Class::Ordinary),
+    ("kappa", 'κ', Class::Ordinary),
+    ("lambda", 'λ', Class::Ordinary),
+    ("mu", 'μ', Class::Ordinary),
+    ("nu", 'ν', Class::Ordinary),
+    ("xi", 'ξ', Class::Ordinary),
+    ("omicron", 'ο', Class::Ordinary),
+    ("pi", 'π', Class::Ordinary),
+    ("varpi", 'ϖ', Class::Ordinary),
+    ("rho", 'ρ', Class::Ordinary),
+    ("varrho", 'ϱ', Class::Ordinary),
+    ("sigma", 'σ', Class::Ordinary),
+    ("varsigma", 'ς', Class::Ordinary),
+    ("tau", 'τ', Class::Ordinary),
+    ("upsilon", 'υ', Class::Ordinary),
+    ("phi", 'ϕ', Class::Ordinary),
+    ("varphi", 'φ', Class::Ordinary),
+    ("chi", 'χ', Class::Ordinary),
+    ("psi", 'ψ', Class::Ordinary),
+    ("omega", 'ω', Class::Ordinary),
+    ("Gamma", 'Γ', Class::Ordinary),
+    ("Delta", 'Δ', Class::Ordinary),
+    ("Theta", 'Θ', Class::Ordinary),
+    ("Lambda", 'Λ', Class::Ordinary),
+    ("Xi", 'Ξ', Class::Ordinary),
+    ("Pi", 'Π', Class::Ordinary),
+    ("Sigma", 'Σ', Class::Ordinary),
+    ("Upsilon", 'Υ', Class::Ordinary),
+    ("Phi", 'Φ', Class::Ordinary),
+    ("Psi", 'Ψ', Class::Ordinary),
+    ("Omega", 'Ω', Class::Ordinary),
+    ("infty", '∞', Class::Ordinary),
+    ("partial", '∂', Class::Ordinary),
+    ("nabla", '∇', Class::Ordinary),
+    ("forall", '∀', Class::Ordinary),
+    ("exists", '∃', Class::Ordinary),
+    ("emptyset", '∅', Class::Ordinary),
+    ("hbar", 'ℏ', Class::Ordinary),
+    ("ell", 'ℓ', Class::Ordinary),
+    ("prime", '′', Class::Ordinary),
+    ("ldots", '…', Class::Ordinary),
+    ("cdots", '⋯', Class::Ordinary),
+    ("pm", '±', Class::Binary),
+    ("mp", '∓', Class::Binary),
+    ("times", '×', Class::Binary),
+    ("cdot", '⋅', Class::Binary),
+    ("div", '÷', Class::Binary),
+    ("ast", '∗', Class::Binary),
+    ("circ", '∘', Class::Binary),
+    ("cup", '∪', Class::Binary),
+    ("cap", '∩', Class::Binary),
+    ("leq", '≤', Class::Relation),
+    ("le", '≤', Class::Relation),
+    ("geq", '≥', Class::Relation),
+    ("ge", '≥', Class::Relation),
+    ("neq", '≠', Class::Relation),
+    ("ne", '≠', Class::Relation),
+    ("approx", '≈', Class::Relation),
+    ("equiv", '≡', Class::Relation),
+    ("sim", '∼', Class::Relation),
+    ("simeq", '≃', Class::Relation),
+    ("propto", '∝', Class::Relation),
+    ("in", '∈', Class::Relation),
+    ("notin", '∉', Class::Relation),
+    ("subset", '⊂', Class::Relation),
+    ("subseteq", '⊆', Class::Relation),
+    ("to", '→', Class::Relation),
+    ("rightarrow", '→', Class::Relation),
+    ("leftarrow", '←', Class::Relation),
+    ("Rightarrow", '⇒', Class::Relation),
+    ("Leftrightarrow", '⇔', Class::Relation),
+    ("mapsto", '↦', Class::Relation),
+    ("{", '{', Class::Open),
+    ("}", '}', Class::Close),
+    ("langle", '⟨', Class::Open),
+    ("rangle", '⟩', Class::Close),
+    ("|", '‖', Class::Ordinary),
+];
+
+/// Large operators, along with whether their scripts are set as limits above and below
+const LARGE_OPERATORS: &[(&str, char, bool)] = &[
+    ("sum", '∑', true),
+    ("prod", '∏', true),
+    ("coprod", '∐', true),
+    ("bigcup", '⋃', true),
+    ("bigcap", '⋂', true),
+    ("int", '∫', false),
+    ("iint", '∬', false),
+    ("oint", '∮', false),
+];
+
+/// Function names set upright, along with whether their scripts are set as limits above and below
+const FUNCTIONS: &[(&str, bool)] = &[
+    ("sin", false),
+    ("cos", false),
+    ("tan", false),
+    ("cot", false),
+    ("sec", false),
+    ("csc", false),
+    ("arcsin", false),
+    ("arccos", false),
+    ("arctan", false),
+    ("sinh", false),
+    ("cosh", false),
+    ("tanh", false),
+    ("log", false),
+    ("ln", false),
+    ("exp", false),
+    ("det", false),
+    ("dim", false),
+    ("ker", false),
+    ("deg", false),
+    ("arg", false),
+    ("gcd", false),
+    ("lim", true),
+    ("max", true),
+    ("min", true),
+    ("sup", true),
+    ("inf", true),
+];
+
+/// Delimiters after `\left` and `\right` given as commands
+const DELIMITERS: &[(&str, char)] = &[
+    ("{", '{'),
+    ("}", '}'),
+    ("|", '‖'),
+    ("langle", '⟨'),
+    ("rangle", '⟩'),
+    ("lfloor", '⌊'),
+    ("rfloor", '⌋'),
+    ("lceil", '⌈'),
+    ("rceil", '⌉'),
+    ("vert", '|'),
+    ("Vert", '‖'),
+];
+
+/// Parses a formula in a subset of TeX's math mode
+pub(crate) fn parse(source: &str) -> Result<Vec<Node>, MathError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+    };
+    let nodes = parser.parse_list(None)?;
+
+    // Lists only stop early at tokens that close something that was never opened
+    match parser.peek() {
+        None => Ok(nodes),
+        Some('}') => Err(MathError::UnbalancedBraces),
+        Some('&') => Err(MathError::Unexpected("&".to_string())),
+        Some(_) => Err(MathError::Unexpected(format!(
+            "\\{}",
+            parser.command_name()
+        ))),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.position += 1;
+
+        Some(char)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Returns the name of the command starting at the current position, without consuming it
+    fn command_name(&self) -> String {
+        let rest = &self.chars[(self.position + 1).min(self.chars.len())..];
+        let letters = rest
+            .iter()
+            .take_while(|char| char.is_ascii_alphabetic())
+            .count();
+
+        // Commands are either a run of letters or a single other character like `\,`
+        rest[..letters.max(1).min(rest.len())].iter().collect()
+    }
+
+    fn at_command(&self, name: &str) -> bool {
+        self.peek() == Some('\\') && self.command_name() == name
+    }
+
+    /// Whether the list being parsed ends here, which is up to the caller to handle
+    fn at_list_end(&self, closing: Option<char>) -> bool {
+        match self.peek() {
+            None | Some('}') | Some('&') => true,
+            Some('\\') => ["\\", "end", "right"].contains(&self.command_name().as_str()),
+            char => char == closing,
+        }
+    }
+
+    /// Parses atoms and their scripts until the end of the list, **closing** is an additional character that ends it
+    fn parse_list(&mut self, closing: Option<char>) -> Result<Vec<Node>, MathError> {
+        let mut nodes = vec![];
+
+        loop {
+            self.skip_whitespace();
+
+            if self.at_list_end(closing) {
+                return Ok(nodes);
+            }
+
+            match self.peek() {
+                Some(script @ ('^' | '_')) => {
+                    self.position += 1;
+                    let argument = self.parse_argument(&script.to_string())?;
+
+                    // A script without anything before it is attached to an empty base
+                    let base = nodes.pop().unwrap_or(Node::Group(vec![]));
+                    nodes.push(attach_script(base, script, argument)?);
+                }
+                _ => nodes.push(self.parse_atom()?),
+            }
+        }
+    }
+
+    /// Parses a single character, command or group in braces
+    fn parse_atom(&mut self) -> Result<Node, MathError> {
+        match self.next() {
+            Some('{') => {
+                let nodes = self.parse_list(None)?;
+                self.expect_closing_brace()?;
+
+                Ok(Node::Group(nodes))
+            }
+            Some('\\') => self.parse_command(),
+            Some(char) => Ok(symbol(char)),
+            None => Err(MathError::UnbalancedBraces),
+        }
+    }
+
+    /// Parses the argument of a command or script, either a group in braces or a single atom
+    fn parse_argument(&mut self, command: &str) -> Result<Node, MathError> {
+        self.skip_whitespace();
+
+        if self.at_list_end(None) {
+            return Err(MathError::MissingArgument(command.to_string()));
+        }
+
+        self.parse_atom()
+    }
+
+    /// Parses an argument like `parse_argument` and returns the nodes inside of it
+    fn parse_list_argument(&mut self, command: &str) -> Result<Vec<Node>, MathError> {
+        Ok(match self.parse_argument(command)? {
+            Node::Group(nodes) => nodes,
+            node => vec![node],
+        })
+    }
+
+    /// Reads the raw text of an argument in braces, like the name of an environment
+    fn parse_text_argument(&mut self, command: &str) -> Result<String, MathError> {
+        self.skip_whitespace();
+
+        if self.next() != Some('{') {
+            return Err(MathError::MissingArgument(command.to_string()));
+        }
+
+        let mut text = String::new();
+        let mut depth = 0;
+
+        loop {
+            match self.next() {
+                Some('}') if depth == 0 => return Ok(text),
+                Some(char) => {
+                    match char {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    text.push(char);
+                }
+                None => return Err(MathError::UnbalancedBraces),
+            }
+        }
+    }
+
+    fn expect_closing_brace(&mut self) -> Result<(), MathError> {
+        match self.next() {
+            Some('}') => Ok(()),
+            _ => Err(MathError::UnbalancedBraces),
+        }
+    }
+
+    /// Parses the command after a backslash
+    fn parse_command(&mut self) -> Result<Node, MathError> {
+        // The backslash is consumed already, step back to read the name
+        self.position -= 1;
+        let name = self.command_name();
+        self.position += 1 + name.chars().count();
+
+        let command = format!("\\{}", name);
+        let space = |eighteenths: f32| Ok(Node::Space(eighteenths / 18.0));
+
+        match name.as_str() {
+            "frac" => Ok(Node::Fraction {
+                numerator: self.parse_list_argument(&command)?,
+                denominator: self.parse_list_argument(&command)?,
+            }),
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.peek() == Some('[') {
+                    self.position += 1;
+                    let index = self.parse_list(Some(']'))?;
+                    if self.next() != Some(']') {
+                        return Err(MathError::Unclosed("[".to_string()));
+                    }
+                    Some(index)
+                } else {
+                    None
+                };
+
+                Ok(Node::Root {
+                    index,
+                    radicand: self.parse_list_argument(&command)?,
+                })
+            }
+            "text" | "mathrm" => Ok(Node::Text(self.parse_text_argument(&command)?)),
+            "left" => {
+                let open = self.parse_delimiter(&command)?;
+                let content = self.parse_list(None)?;
+
+                if !self.at_command("right") {
+                    return Err(MathError::Unclosed(command));
+                }
+                self.position += "\\right".len();
+
+                Ok(Node::Delimited {
+                    open,
+                    close: self.parse_delimiter("\\right")?,
+                    content,
+                })
+            }
+            "begin" => self.parse_environment(),
+            "," => space(3.0),
+            ":" | ">" => space(4.0),
+            ";" => space(5.0),
+            "!" => space(-3.0),
+            " " => space(6.0),
+            "quad" => space(18.0),
+            "qquad" => space(36.0),
+            _ => {
+                if let Some((_, char, class)) = SYMBOLS.iter().find(|symbol| symbol.0 == name) {
+                    return Ok(Node::Symbol {
+                        char: italic(*char),
+                        class: *class,
+                    });
+                }
+
+                if let Some((_, char, limits)) =
+                    LARGE_OPERATORS.iter().find(|operator| operator.0 == name)
+                {
+                    return Ok(Node::Operator {
+                        text: char.to_string(),
+                        large: true,
+                        limits: *limits,
+                    });
+                }
+
+                if let Some((_, limits)) = FUNCTIONS.iter().find(|function| function.0 == name) {
+                    return Ok(Node::Operator {
+                        text: name,
+                        large: false,
+                        limits: *limits,
+                    });
+                }
+
+                Err(MathError::UnknownCommand(command))
+            }
+        }
+    }
+
+    /// Parses the delimiter after `\left` or `\right`, `.` stands for none
+    fn parse_delimiter(&mut self, command: &str) -> Result<Option<char>, MathError> {
+        self.skip_whitespace();
+
+        match self.next() {
+            Some('.') => Ok(None),
+            Some('\\') => {
+                self.position -= 1;
+                let name = self.command_name();
+                self.position += 1 + name.chars().count();
+
+                DELIMITERS
+                    .iter()
+                    .find(|delimiter| delimiter.0 == name)
+                    .map(|delimiter| Some(delimiter.1))
+                    .ok_or(MathError::UnknownCommand(format!("\\{}", name)))
+            }
+            Some(char) if !char.is_alphanumeric() && char != '{' && char != '}' => Ok(Some(char)),
+            _ => Err(MathError::MissingArgument(command.to_string())),
+        }
+    }
+
+    /// Parses a matrix environment after `\begin`, cells are separated by `&` and rows by `\\`
+    fn parse_environment(&mut self) -> Result<Node, MathError> {
+        let name = self.parse_text_argument("\\begin")?;
+        let (open, close) = match name.as_str() {
+            "matrix" => (None, None),
+            "pmatrix" => (Some('('), Some(')')),
+            "bmatrix" => (Some('['), Some(']')),
+            "Bmatrix" => (Some('{'), Some('}')),
+            "vmatrix" => (Some('|'), Some('|')),
+            "Vmatrix" => (Some('‖'), Some('‖')),
+            "cases" => (Some('{'), None),
+            _ => return Err(MathError::UnknownEnvironment(name)),
+        };
+
+        let mut rows = vec![];
+        let mut row = vec![];
+
+        loop {
+            row.push(self.parse_list(None)?);
+
+            if self.peek() == Some('&') {
+                self.position += 1;
+            } else if self.at_command("\\") {
+                self.position += 2;
+                rows.push(std::mem::take(&mut row));
+            } else if self.at_command("end") {
+                self.position += "\\end".len();
+                if self.parse_text_argument("\\end")? != name {
+                    return Err(MathError::Unclosed(format!("\\begin{{{}}}", name)));
+                }
+                break;
+            } else {
+                return Err(MathError::Unclosed(format!("\\begin{{{}}}", name)));
+            }
+        }
+
+        // A trailing `\\` doesn't start another row
+        if row.len() > 1 || row.first().is_some_and(|cell| !cell.is_empty()) {
+            rows.push(row);
+        }
+
+        Ok(Node::Matrix {
+            open,
+            close,
+            rows,
+            left_aligned: name == "cases",
+        })
+    }
+}
+
+/// Attaches a superscript or subscript to the **base**, a base may have one of each
+fn attach_script(base: Node, script: char, argument: Node) -> Result<Node, MathError> {
+    let (base, mut superscript, mut subscript) = match base {
+        Node::Scripts {
+            base,
+            superscript,
+            subscript,
+        } => (base, superscript, subscript),
+        base => (Box::new(base), None, None),
+    };
+
+    let slot = if script == '^' {
+        &mut superscript
+    } else {
+        &mut subscript
+    };
+    if slot.is_some() {
+        return Err(MathError::Unexpected(format!("double {}", script)));
+    }
+    *slot = Some(Box::new(argument));
+
+    Ok(Node::Scripts {
+        base,
+        superscript,
+        subscript,
+    })
+}
+
+/// Returns the node of a plain character in a formula
+fn symbol(char: char) -> Node {
+    let (char, class) = match char {
+        '+' => ('+', Class::Binary),
+        '-' => ('−', Class::Binary),
+        '*' => ('∗', Class::Binary),
+        '=' | '<' | '>' | ':' => (char, Class::Relation),
+        '(' | '[' => (char, Class::Open),
+        ')' | ']' => (char, Class::Close),
+        ',' | ';' => (char, Class::Punctuation),
+        '\'' => ('′', Class::Ordinary),
+        _ => (italic(char), Class::Ordinary),
+    };
+
+    Node::Symbol { char, class }
+}
+
+/// Maps latin and lowercase greek letters to their math italic forms, like TeX sets variables
+fn italic(char: char) -> char {
+    let offset = |first: char, base: u32| char::from_u32(base + (char as u32 - first as u32));
+
+    match char {
+        // The italic h has been encoded before the block of math italic letters
+        'h' => Some('ℎ'),
+        'a'..='z' => offset('a', 0x1D44E),
+        'A'..='Z' => offset('A', 0x1D434),
+        'α'..='ω' => offset('α', 0x1D6FC),
+        '∂' => Some('\u{1D715}'),
+        'ϵ' => Some('\u{1D716}'),
+        'ϑ' => Some('\u{1D717}'),
+        'ϰ' => Some('\u{1D718}'),
+        'ϕ' => Some('\u{1D719}'),
+        'ϱ' => Some('\u{1D71A}'),
+        'ϖ' => Some('\u{1D71B}'),
+        _ => None,
+    }
+    .unwrap_or(char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions_hold_their_numerator_and_denominator() {
+        let nodes = parse(r"\frac{a+1}{2}").unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![Node::Fraction {
+                numerator: vec![symbol('a'), symbol('+'), symbol('1')],
+                denominator: vec![symbol('2')],
+            }]
+        );
+    }
+
+    #[test]
+    fn roots_take_an_optional_index() {
+        let nodes = parse(r"\sqrt[3]{x} \sqrt y").unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![
+                Node::Root {
+                    index: Some(vec![symbol('3')]),
+                    radicand: vec![symbol('x')],
+                },
+                Node::Root {
+                    index: None,
+                    radicand: vec![symbol('y')],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn scripts_attach_in_either_order() {
+        let expected = vec![Node::Scripts {
+            base: Box::new(symbol('x')),
+            superscript: Some(Box::new(symbol('2'))),
+            subscript: Some(Box::new(Node::Group(vec![symbol('i'), symbol('j')]))),
+        }];
+
+        assert_eq!(parse("x^2_{ij}").unwrap(), expected);
+        assert_eq!(parse("x_{ij}^2").unwrap(), expected);
+    }
+
+    #[test]
+    fn trailing_row_breaks_in_matrices_are_ignored() {
+        let nodes = parse(r"\begin{pmatrix} a & b \\ c & d \\ \end{pmatrix}").unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![Node::Matrix {
+                open: Some('('),
+                close: Some(')'),
+                rows: vec![
+                    vec![vec![symbol('a')], vec![symbol('b')]],
+                    vec![vec![symbol('c')], vec![symbol('d')]],
+                ],
+                left_aligned: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_formulas_report_what_is_wrong() {
+        let cases = [
+            ("{x", MathError::UnbalancedBraces),
+            ("x}", MathError::UnbalancedBraces),
+            (r"\foo", MathError::UnknownCommand(r"\foo".to_string())),
+            (
+                r"\begin{foo}\end{foo}",
+                MathError::UnknownEnvironment("foo".to_string()),
+            ),
+            (
+                r"\frac{1}",
+                MathError::MissingArgument(r"\frac".to_string()),
+            ),
+            (r"\left( x", MathError::Unclosed(r"\left".to_string())),
+            (r"\sqrt[3{x}", MathError::Unclosed("[".to_string())),
+            ("x^2^3", MathError::Unexpected("double ^".to_string())),
+            ("a & b", MathError::Unexpected("&".to_string())),
+        ];
+
+        for (source, error) in cases {
+            assert_eq!(parse(source), Err(error), "{}", source);
+        }
+    }
+}
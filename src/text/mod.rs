@@ -0,0 +1,14 @@
+mod code;
+mod font;
+mod glyphs;
+mod layout;
+pub(crate) mod math;
+mod rich;
+
+pub use code::{Code, CodeStyle};
+pub use font::{Font, FontError};
+pub use glyphs::{Reveal, RevealUnit, TextGlyphs};
+pub use layout::TextAlign;
+pub(crate) use layout::{layout, LayoutCache, LayoutKey, LayoutOptions, TextLayout};
+pub use math::MathError;
+pub use rich::RichText;
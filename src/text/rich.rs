@@ -0,0 +1,113 @@
+use crate::text::{Font, TextAlign};
+use crate::{Group, Text};
+use lyon::math::point;
+
+/// Slant of italic spans in radians, the bundled font has no italic variant so they are skewed
+const ITALIC_SKEW: f32 = 0.2;
+
+/// A **RichText** made of spans of **Text**, each with its own font, size, color and underline, set one after another
+///
+/// Spans continue where the previous span ended and `\n` inside a span starts a new line below, spans with the same
+/// **max_width** wrap together. Lines are left aligned. Spans are ordinary elements, so each one can be animated on its own.
+pub struct RichText {
+    /// Group containing all spans, move it to place the whole text
+    pub group: Group,
+    /// Spans in reading order
+    pub spans: Vec<Text>,
+}
+
+impl RichText {
+    /// Sets the **spans** one after another, their position, indent and alignment are replaced
+    pub fn new(mut spans: Vec<Text>) -> Self {
+        let mut group = Group::default();
+        let mut pen = point(0.0, 0.0);
+
+        for span in &mut spans {
+            span.x = 0.0;
+            span.y = pen.y;
+            span.indent = pen.x;
+            span.align = TextAlign::Left;
+
+            // The end of a span is relative to the baseline of its first line
+            let end = span.end();
+            pen = point(end.x, pen.y + end.y);
+
+            group.add(span);
+        }
+
+        RichText { group, spans }
+    }
+
+    /// Parses **markup** with `**bold**`, `*italic*` and `__underlined__` parts into spans styled like the **base** text
+    ///
+    /// Bold parts use `Font::default_bold` and italic parts are slanted, a backslash escapes the next character.
+    pub fn markup(markup: &str, base: &Text) -> Self {
+        let mut spans = vec![];
+        let mut text = String::new();
+        let mut style = SpanStyle::default();
+        let mut chars = markup.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            let toggle = match char {
+                '\\' => {
+                    text.extend(chars.next());
+                    continue;
+                }
+                '*' if chars.next_if_eq(&'*').is_some() => {
+                    |style: &mut SpanStyle| style.bold ^= true
+                }
+                '*' => |style: &mut SpanStyle| style.italic ^= true,
+                '_' if chars.next_if_eq(&'_').is_some() => {
+                    |style: &mut SpanStyle| style.underline ^= true
+                }
+                _ => {
+                    text.push(char);
+                    continue;
+                }
+            };
+
+            // Every change of style ends the current span
+            if !text.is_empty() {
+                spans.push(style.apply(std::mem::take(&mut text), base));
+            }
+            toggle(&mut style);
+        }
+
+        if !text.is_empty() {
+            spans.push(style.apply(text, base));
+        }
+
+        RichText::new(spans)
+    }
+}
+
+/// Style toggled by the markup of a **RichText**
+#[derive(Default)]
+struct SpanStyle {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl SpanStyle {
+    /// Creates a span of the **text** styled like the **base** with this style on top
+    fn apply(&self, text: String, base: &Text) -> Text {
+        let base = base.duplicate_without_keyframes();
+
+        Text {
+            text,
+            font: if self.bold {
+                Font::default_bold()
+            } else {
+                base.font.clone()
+            },
+            skew_x: if self.italic {
+                ITALIC_SKEW
+            } else {
+                base.skew_x
+            },
+            underline: self.underline || base.underline,
+            ..base
+        }
+    }
+}